@@ -2,16 +2,34 @@
 
 pub use pallet::*;
 
+pub mod migrations;
+pub mod runtime_api;
+pub mod traits;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
 #[frame_support::pallet(dev_mode)]
 pub mod pallet {
     use super::*;
+    use crate::traits::{
+        AttestationProvider, CreatorRewardCurve, DepositScaling, DepositStaker, IsEligibleWinner,
+        PriceOracle,
+    };
     use frame_support::pallet_prelude::*;
     use frame_support::sp_runtime::traits::AccountIdConversion;
     use frame_support::sp_runtime::traits::CheckedDiv;
     use frame_support::sp_runtime::traits::Zero;
+    use frame_support::sp_runtime::Permill;
     use frame_support::sp_runtime::Saturating;
     use frame_support::traits::Currency;
     use frame_support::traits::ExistenceRequirement;
+    use frame_support::traits::ReservableCurrency;
+    use frame_support::traits::Time;
+    use frame_support::traits::WithdrawReasons;
     use frame_support::transactional;
     use frame_support::PalletId;
     use frame_system::pallet_prelude::*;
@@ -19,15 +37,22 @@ pub mod pallet {
 
     type BalanceOf<T> =
         <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+    type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+        <T as frame_system::Config>::AccountId,
+    >>::NegativeImbalance;
+    type MomentOf<T> = <<T as Config>::ReferenceTime as Time>::Moment;
+
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
 
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     #[pallet::config]
     pub trait Config: frame_system::Config + scale_info::TypeInfo {
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 
-        type Currency: Currency<Self::AccountId>;
+        type Currency: ReservableCurrency<Self::AccountId>;
 
         #[pallet::constant]
         type PalletId: Get<PalletId>;
@@ -40,6 +65,139 @@ pub mod pallet {
 
         #[pallet::constant]
         type MarketDuration: Get<u32>;
+
+        /// Checks that a voter holds the attestation required to participate.
+        type VoterAttestation: AttestationProvider<Self::AccountId>;
+
+        /// Number of final blocks before `end_block` during which large stakes are rejected.
+        #[pallet::constant]
+        type StakeFreezeWindow: Get<u32>;
+
+        /// Largest stake a single vote may add inside the freeze window. Each vote's quoted charge
+        /// is compared on its own, so small votes stay allowed however many are cast.
+        #[pallet::constant]
+        type LateStakeCap: Get<BalanceOf<Self>>;
+
+        /// Origin allowed to moderate markets.
+        type GovernanceOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Votes by which the winning side must lead, otherwise the market is voided.
+        #[pallet::constant]
+        type MinWinMargin: Get<u32>;
+
+        /// Base deposit reserved from a creator for each market until it resolves.
+        #[pallet::constant]
+        type CreationDeposit: Get<BalanceOf<Self>>;
+
+        /// Scales the creation deposit with the creator's number of active markets.
+        type DepositScaling: DepositScaling<BalanceOf<Self>>;
+
+        /// Where creation deposits are held while their market runs.
+        type DepositStaker: DepositStaker<Self::AccountId, Self::Currency>;
+
+        #[pallet::constant]
+        type MaxMarketsPerCreator: Get<u32>;
+
+        /// Fewest voters the winning side needs to be paid, otherwise the market is voided.
+        #[pallet::constant]
+        type MinWinnersToPay: Get<u32>;
+
+        /// Derives the creator's percentage from `CreatorRewardPercentage` and the pool size.
+        type CreatorRewardCurve: CreatorRewardCurve<BalanceOf<Self>>;
+
+        /// What happens to the creator's deposit when a market ties or is voided.
+        #[pallet::constant]
+        type DepositOnVoid: Get<DepositPolicy>;
+
+        /// Account receiving slashed deposits.
+        #[pallet::constant]
+        type TreasuryAccount: Get<Self::AccountId>;
+
+        /// Votes after which a market stops accepting votes and can be resolved early.
+        #[pallet::constant]
+        type MaxVotesPerMarket: Get<u32>;
+
+        /// Fee-free first votes in fresh markets each account may use.
+        #[pallet::constant]
+        type FreeVoteQuota: Get<u32>;
+
+        /// Wall-clock time source for markets that resolve after a reference moment.
+        type ReferenceTime: Time;
+
+        /// Share of each resolved market's pool that is burned before any payouts.
+        #[pallet::constant]
+        type BurnPercentage: Get<Permill>;
+
+        /// Whether governance may move an `end_block` into the past once votes exist.
+        #[pallet::constant]
+        type AllowRetroactiveEndBlock: Get<bool>;
+
+        /// Share of the winners' pool sent to `TreasuryAccount` at resolution.
+        #[pallet::constant]
+        type HousePercentage: Get<Permill>;
+
+        /// Blocks past `end_block` an unresolved market waits before escalating to governance.
+        #[pallet::constant]
+        type EscalationDelay: Get<u32>;
+
+        /// Markets inspected for escalation in each block's `on_initialize`.
+        #[pallet::constant]
+        type MaxEscalationChecksPerBlock: Get<u32>;
+
+        /// Capacity of the governance resolution queue.
+        #[pallet::constant]
+        type MaxEscalatedMarkets: Get<u32>;
+
+        /// Extra conditions a winning voter must meet to share in the pool.
+        type WinnerEligibility: IsEligibleWinner<Self::AccountId>;
+
+        /// Blocks before `end_block` at which tallies are frozen for resolution; 0 disables.
+        #[pallet::constant]
+        type SnapshotOffset: Get<u32>;
+
+        /// Whether to emit a `BlockSummary` event of each block's market activity.
+        #[pallet::constant]
+        type EmitBlockSummary: Get<bool>;
+
+        /// Minimum blocks between an account's votes in any markets; 0 disables.
+        #[pallet::constant]
+        type VoteCooldown: Get<u32>;
+
+        /// Whether a market's creator may vote in it.
+        #[pallet::constant]
+        type AllowCreatorVoting: Get<bool>;
+
+        /// Stake reserved from an account while it is registered as a keeper.
+        #[pallet::constant]
+        type KeeperBond: Get<BalanceOf<Self>>;
+
+        /// Paid from `TreasuryAccount` to a registered keeper for each market it resolves.
+        #[pallet::constant]
+        type KeeperTip: Get<BalanceOf<Self>>;
+
+        /// Blocks a deregistered keeper's bond stays reserved, and slashable, before it can be
+        /// withdrawn.
+        #[pallet::constant]
+        type KeeperUnbondingPeriod: Get<u32>;
+
+        /// Converts `VoteCost` into the native amount charged per vote.
+        type PriceOracle: PriceOracle<BalanceOf<Self>>;
+
+        /// Most a single winner can be paid from one market; the excess goes to the treasury.
+        #[pallet::constant]
+        type MaxRewardPerAccount: Get<BalanceOf<Self>>;
+
+        /// Whether to close voting as soon as the remaining capped votes can't change the result.
+        #[pallet::constant]
+        type CloseWhenDecided: Get<bool>;
+
+        /// Prior resolved markets a winner needs to have voted in to earn `LoyaltyBonus`.
+        #[pallet::constant]
+        type LoyaltyThreshold: Get<u32>;
+
+        /// Paid from `TreasuryAccount` to each loyal winner on top of their winnings.
+        #[pallet::constant]
+        type LoyaltyBonus: Get<BalanceOf<Self>>;
     }
 
     #[pallet::storage]
@@ -59,6 +217,83 @@ pub mod pallet {
         ValueQuery,
     >;
 
+    /// Votes cast after a market's snapshot; they stake but neither sway nor share the outcome.
+    #[pallet::storage]
+    pub type LateVotes<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, u32, Blake2_128Concat, T::AccountId, (), OptionQuery>;
+
+    #[pallet::storage]
+    pub type MarketsByCreator<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedVec<u32, T::MaxMarketsPerCreator>,
+        ValueQuery,
+    >;
+
+    /// Where a voter's winnings are paid; defaults to the voter when unset.
+    #[pallet::storage]
+    pub type PayoutBeneficiaries<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, T::AccountId, OptionQuery>;
+
+    #[pallet::storage]
+    pub type FreeVotesUsed<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    /// Markets under moderation review; new votes are rejected while flagged.
+    #[pallet::storage]
+    pub type FlaggedMarkets<T: Config> = StorageMap<_, Blake2_128Concat, u32, (), OptionQuery>;
+
+    /// Lifetime stake and payout totals per account, across every market.
+    #[pallet::storage]
+    pub type UserStats<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, VoterStats<BalanceOf<T>>, ValueQuery>;
+
+    /// Stuck markets awaiting operator intervention.
+    #[pallet::storage]
+    pub type GovernanceResolutionQueue<T: Config> =
+        StorageValue<_, BoundedVec<u32, T::MaxEscalatedMarkets>, ValueQuery>;
+
+    /// Next market id the escalation scan inspects.
+    #[pallet::storage]
+    pub type EscalationCursor<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    /// Block of each account's most recent vote, tracked while `VoteCooldown` is enabled.
+    #[pallet::storage]
+    pub type LastVotedAt<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, BlockNumberFor<T>, OptionQuery>;
+
+    /// Native amount each vote was charged, refunded as-is if the market is voided.
+    #[pallet::storage]
+    pub type VoteStakes<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        u32,
+        Blake2_128Concat,
+        T::AccountId,
+        BalanceOf<T>,
+        OptionQuery,
+    >;
+
+    /// The most recent per-vote charge, used while the price oracle is unavailable.
+    #[pallet::storage]
+    pub type LastVoteCharge<T: Config> = StorageValue<_, BalanceOf<T>, OptionQuery>;
+
+    /// Keepers and the bond each has reserved, with the block the bond unlocks at once the
+    /// keeper has deregistered.
+    #[pallet::storage]
+    pub type Keepers<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        (BalanceOf<T>, Option<BlockNumberFor<T>>),
+        OptionQuery,
+    >;
+
+    /// Markets created, resolved and voided in the current block; cleared in `on_finalize`.
+    #[pallet::storage]
+    pub type BlockActivity<T: Config> = StorageValue<_, BlockActivityCounters, ValueQuery>;
+
     #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
     pub struct Market<T: Config> {
         pub creator: T::AccountId,
@@ -67,6 +302,14 @@ pub mod pallet {
         pub no_votes: u32,
         pub total_staked: BalanceOf<T>,
         pub is_active: bool,
+        pub voting_closed: bool,
+        pub resolve_after: Option<MomentOf<T>>,
+        pub resolved_outcome: Option<Outcome>,
+        /// Block in which `release_rewards` resolved the market.
+        pub resolved_at: Option<BlockNumberFor<T>>,
+        /// `(yes_votes, no_votes)` as of the snapshot block, once a later vote has been cast.
+        pub snapshot: Option<(u32, u32)>,
+        pub deposit: BalanceOf<T>,
         pub metadata: BoundedVec<u8, ConstU32<256>>,
         pub memes: BoundedVec<Meme<T>, ConstU32<100>>
     }
@@ -78,6 +321,67 @@ pub mod pallet {
         pub meme_template: u32,
     }
 
+    #[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+    pub enum Outcome {
+        Yes,
+        No,
+        Tie,
+        Void,
+    }
+
+    /// SCALE-encoded summary of a resolved market. It carries no signature or proof, so another
+    /// chain can only trust it through this chain's state proofs or a signature added off-chain.
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+    pub struct OutcomeAttestation<Hash, BlockNumber, Balance> {
+        /// Genesis hash of the chain that resolved the market.
+        pub genesis_hash: Hash,
+        pub market_id: u32,
+        pub outcome: Outcome,
+        pub end_block: BlockNumber,
+        pub resolved_at: BlockNumber,
+        pub yes_votes: u32,
+        pub no_votes: u32,
+        pub total_staked: Balance,
+    }
+
+    #[derive(
+        Clone, Default, Encode, Decode, Eq, PartialEq, RuntimeDebug, MaxEncodedLen, TypeInfo,
+    )]
+    pub struct VoterStats<Balance> {
+        pub total_staked: Balance,
+        /// Winnings and refunds paid back to the account.
+        pub total_won: Balance,
+        /// Markets the account voted in that resolved to an outcome.
+        pub markets_resolved: u32,
+    }
+
+    #[derive(
+        Clone, Default, Encode, Decode, Eq, PartialEq, RuntimeDebug, MaxEncodedLen, TypeInfo,
+    )]
+    pub struct BlockActivityCounters {
+        pub created: u32,
+        pub resolved: u32,
+        /// Markets voided instead of paying out.
+        pub cancelled: u32,
+    }
+
+    #[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+    pub enum DepositPolicy {
+        /// Unreserve the deposit back to the creator.
+        Return,
+        /// Move the deposit to the treasury.
+        Slash,
+        /// Destroy the deposit.
+        Burn,
+    }
+
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+    pub enum VoidReason {
+        MarginNotMet,
+        TooFewWinners,
+        NoEligibleWinners,
+    }
+
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
@@ -103,6 +407,81 @@ pub mod pallet {
             cid: BoundedVec<u8, ConstU32<256>>,
             template_id: u32,
         },
+        MarketFlagged {
+            market_id: u32,
+        },
+        MarketFlagCleared {
+            market_id: u32,
+        },
+        MarketVoided {
+            market_id: u32,
+            reason: VoidReason,
+        },
+        MetadataAppended {
+            market_id: u32,
+            metadata: BoundedVec<u8, ConstU32<256>>,
+        },
+        PayoutBeneficiarySet {
+            who: T::AccountId,
+            beneficiary: T::AccountId,
+        },
+        VotingClosed {
+            market_id: u32,
+        },
+        ResolveAfterSet {
+            market_id: u32,
+            resolve_after: MomentOf<T>,
+        },
+        PoolBurned {
+            market_id: u32,
+            amount: BalanceOf<T>,
+        },
+        EndBlockSet {
+            market_id: u32,
+            end_block: BlockNumberFor<T>,
+        },
+        HouseEdgeCollected {
+            market_id: u32,
+            amount: BalanceOf<T>,
+        },
+        MarketEscalated {
+            market_id: u32,
+        },
+        BlockSummary {
+            created: u32,
+            resolved: u32,
+            cancelled: u32,
+        },
+        KeeperRegistered {
+            who: T::AccountId,
+            bond: BalanceOf<T>,
+        },
+        KeeperDeregistered {
+            who: T::AccountId,
+            unbond_at: BlockNumberFor<T>,
+        },
+        KeeperBondWithdrawn {
+            who: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+        KeeperSlashed {
+            who: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+        KeeperTipPaid {
+            keeper: T::AccountId,
+            market_id: u32,
+            amount: BalanceOf<T>,
+        },
+        RewardCapExcessCollected {
+            market_id: u32,
+            amount: BalanceOf<T>,
+        },
+        LoyaltyBonusPaid {
+            market_id: u32,
+            who: T::AccountId,
+            amount: BalanceOf<T>,
+        },
     }
 
     #[pallet::error]
@@ -115,6 +494,63 @@ pub mod pallet {
         MetadataTooLong,
         MemeListFull,
         InvalidCID,
+        AttestationRequired,
+        LateStakeTooLarge,
+        MarketUnderReview,
+        TooManyMarkets,
+        NotMarketCreator,
+        MetadataLocked,
+        InsufficientDeposit,
+        VotingClosed,
+        ResolveAfterLocked,
+        ReferenceTimeNotReached,
+        EndBlockInPast,
+        InsufficientPoolBalance,
+        VoteCooldownActive,
+        CreatorCannotVote,
+        AlreadyKeeper,
+        NotKeeper,
+        PriceUnavailable,
+        KeeperBondLocked,
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+            // Reserve the `on_finalize` summary's read and reset up front.
+            let summary_weight = if T::EmitBlockSummary::get() {
+                T::DbWeight::get().reads_writes(1, 1)
+            } else {
+                Weight::zero()
+            };
+            Self::escalate_overdue_markets(now).saturating_add(summary_weight)
+        }
+
+        fn integrity_test() {
+            // Each skim is taken from what the previous one left, but together they must still
+            // not promise more than the whole pool.
+            let allocated = u64::from(T::CreatorRewardPercentage::get()) * 10_000
+                + u64::from(T::HousePercentage::get().deconstruct())
+                + u64::from(T::BurnPercentage::get().deconstruct());
+            assert!(
+                allocated <= u64::from(Permill::one().deconstruct()),
+                "creator reward, house edge and burn percentages exceed 100%"
+            );
+        }
+
+        fn on_finalize(_now: BlockNumberFor<T>) {
+            if !T::EmitBlockSummary::get() {
+                return;
+            }
+            let activity = BlockActivity::<T>::take();
+            if activity != BlockActivityCounters::default() {
+                Self::deposit_event(Event::BlockSummary {
+                    created: activity.created,
+                    resolved: activity.resolved,
+                    cancelled: activity.cancelled,
+                });
+            }
+        }
     }
 
     #[pallet::call]
@@ -124,34 +560,7 @@ pub mod pallet {
         pub fn create_market(origin: OriginFor<T>, metadata: Vec<u8>) -> DispatchResult {
             let creator = ensure_signed(origin)?;
 
-            let market_id = MarketCount::<T>::get();
-            let end_block = frame_system::Pallet::<T>::block_number()
-                .saturating_add(T::MarketDuration::get().into());
-
-            let bounded_metadata: BoundedVec<_, _> = metadata
-                .try_into()
-                .map_err(|_| Error::<T>::MetadataTooLong)?;
-
-            let market = Market {
-                creator: creator.clone(),
-                end_block,
-                yes_votes: 0,
-                no_votes: 0,
-                total_staked: Zero::zero(),
-                is_active: true,
-                metadata: bounded_metadata.clone(),
-                memes: BoundedVec::default(),
-            };
-
-            Markets::<T>::insert(market_id, market);
-            MarketCount::<T>::put(market_id.saturating_add(1));
-
-            Self::deposit_event(Event::MarketCreated {
-                market_id,
-                creator,
-                end_block,
-                metadata: bounded_metadata,
-            });
+            Self::do_create_market(creator, metadata)?;
 
             Ok(())
         }
@@ -159,89 +568,147 @@ pub mod pallet {
         #[pallet::call_index(1)]
         #[pallet::weight(10_000)]
         #[transactional]
-        pub fn vote(origin: OriginFor<T>, market_id: u32, vote_yes: bool) -> DispatchResult {
+        pub fn vote(
+            origin: OriginFor<T>,
+            market_id: u32,
+            vote_yes: bool,
+        ) -> DispatchResultWithPostInfo {
             let voter = ensure_signed(origin)?;
 
-            let mut market = Markets::<T>::get(market_id).ok_or(Error::<T>::MarketDoesNotExist)?;
-            ensure!(market.is_active, Error::<T>::MarketNotActive);
-            ensure!(
-                frame_system::Pallet::<T>::block_number() <= market.end_block,
-                Error::<T>::MarketNotActive
-            );
-            ensure!(
-                !Votes::<T>::contains_key(market_id, &voter),
-                Error::<T>::AlreadyVoted
-            );
-
-            // Transfer vote cost
-            T::Currency::transfer(
-                &voter,
-                &Self::account_id(),
-                T::VoteCost::get(),
-                ExistenceRequirement::KeepAlive,
-            )?;
-
-            if vote_yes {
-                market.yes_votes = market.yes_votes.saturating_add(1);
-            } else {
-                market.no_votes = market.no_votes.saturating_add(1);
-            }
-            market.total_staked = market.total_staked.saturating_add(T::VoteCost::get());
-
-            Markets::<T>::insert(market_id, market);
-            Votes::<T>::insert(market_id, &voter, vote_yes);
-
-            Self::deposit_event(Event::VoteCast {
-                market_id,
-                voter,
-                vote: vote_yes,
-            });
+            let pays_fee = Self::do_vote(voter, market_id, vote_yes)?;
 
-            Ok(())
+            Ok(pays_fee.into())
         }
 
         #[pallet::weight(10_000)]
         #[transactional]
         pub fn release_rewards(origin: OriginFor<T>, market_id: u32) -> DispatchResult {
-            let _ = ensure_signed(origin)?;
+            let resolver = ensure_signed(origin)?;
 
             let mut market = Markets::<T>::get(market_id).ok_or(Error::<T>::MarketDoesNotExist)?;
             ensure!(market.is_active, Error::<T>::MarketNotActive);
             ensure!(
-                frame_system::Pallet::<T>::block_number() > market.end_block,
+                market.voting_closed
+                    || frame_system::Pallet::<T>::block_number() > market.end_block,
                 Error::<T>::MarketStillActive
             );
+            ensure!(
+                market
+                    .resolve_after
+                    .map_or(true, |moment| T::ReferenceTime::now() >= moment),
+                Error::<T>::ReferenceTimeNotReached
+            );
+            // Every settlement path pays out at most the pool, so refuse up front rather than
+            // leave some voters paid and others not.
+            ensure!(
+                T::Currency::free_balance(&Self::account_id()) >= market.total_staked,
+                Error::<T>::InsufficientPoolBalance
+            );
 
             market.is_active = false;
+            market.resolved_at = Some(frame_system::Pallet::<T>::block_number());
+            GovernanceResolutionQueue::<T>::mutate(|queue| queue.retain(|id| *id != market_id));
+            // Resolved markets no longer count against the creator's cap.
+            MarketsByCreator::<T>::mutate(&market.creator, |market_ids| {
+                market_ids.retain(|id| *id != market_id)
+            });
+            Self::tip_keeper(&resolver, market_id);
 
-            let total_reward_pool = market.total_staked;
-            let creator_reward = total_reward_pool
-                .saturating_mul(T::CreatorRewardPercentage::get().into())
-                .checked_div(&BalanceOf::<T>::from(100u32))
-                .unwrap_or_else(Zero::zero);
+            let outcome = Self::majority_outcome(&market);
+            let yes_wins = Self::yes_side_wins(outcome);
+            // Eligibility is checked once; the same winners decide voiding and share the pool.
+            let winners = Self::eligible_winners(market_id, yes_wins);
+
+            if let Some(reason) = Self::void_reason(&market, &winners) {
+                Self::settle_deposit(&market, T::DepositOnVoid::get());
+                Self::refund_voters(market_id);
+                market.resolved_outcome = Some(Outcome::Void);
+                Markets::<T>::insert(market_id, market);
+
+                Self::note_activity(|activity| activity.cancelled.saturating_inc());
+                Self::deposit_event(Event::MarketVoided { market_id, reason });
 
-            let remaining_reward_pool = total_reward_pool.saturating_sub(creator_reward);
-            let yes_wins = market.yes_votes > market.no_votes;
-            let winner_count = if yes_wins {
-                market.yes_votes
+                return Ok(());
+            }
+
+            market.resolved_outcome = Some(outcome);
+
+            let deposit_policy = if outcome == Outcome::Tie {
+                T::DepositOnVoid::get()
             } else {
-                market.no_votes
+                DepositPolicy::Return
             };
+            Self::settle_deposit(&market, deposit_policy);
+
+            // Ineligible winners' shares are split among the eligible ones.
+            let winner_count = winners.len() as u32;
+
+            let burned = T::BurnPercentage::get() * market.total_staked;
+            if !burned.is_zero() {
+                let _ = T::Currency::withdraw(
+                    &Self::account_id(),
+                    burned,
+                    WithdrawReasons::TRANSFER,
+                    ExistenceRequirement::AllowDeath,
+                )?;
+                Self::deposit_event(Event::PoolBurned {
+                    market_id,
+                    amount: burned,
+                });
+            }
+
+            let total_reward_pool = market.total_staked.saturating_sub(burned);
+            let creator_reward = Self::creator_reward(total_reward_pool);
+
+            let winners_pool = total_reward_pool.saturating_sub(creator_reward);
+            let house_edge = T::HousePercentage::get() * winners_pool;
+            if !house_edge.is_zero() {
+                // Withdrawn and credited separately since a transfer below the existential
+                // deposit to an empty treasury would fail and block resolution.
+                let edge = T::Currency::withdraw(
+                    &Self::account_id(),
+                    house_edge,
+                    WithdrawReasons::TRANSFER,
+                    ExistenceRequirement::AllowDeath,
+                )?;
+                Self::credit_treasury(edge);
+                Self::deposit_event(Event::HouseEdgeCollected {
+                    market_id,
+                    amount: house_edge,
+                });
+            }
+
+            let remaining_reward_pool = winners_pool.saturating_sub(house_edge);
 
             if winner_count > 0 {
-                let reward_per_winner = remaining_reward_pool
+                let even_share = remaining_reward_pool
                     .checked_div(&BalanceOf::<T>::from(winner_count))
                     .unwrap_or_else(Zero::zero);
+                // Winners share evenly, so a capped share caps every winner and the excess has
+                // no one left to go to but the treasury.
+                let reward_per_winner = even_share.min(T::MaxRewardPerAccount::get());
 
-                for (voter, vote) in Votes::<T>::iter_prefix(market_id) {
-                    if vote == yes_wins {
-                        let _ = T::Currency::transfer(
-                            &Self::account_id(),
-                            &voter,
-                            reward_per_winner,
-                            ExistenceRequirement::AllowDeath,
-                        );
-                    }
+                for voter in winners {
+                    let dest = Self::payout_account(&voter);
+                    Self::pay_voter(&voter, &dest, reward_per_winner);
+                    Self::pay_loyalty_bonus(market_id, &voter, &dest);
+                }
+
+                let excess = even_share
+                    .saturating_sub(reward_per_winner)
+                    .saturating_mul(winner_count.into());
+                if !excess.is_zero() {
+                    let excess_credit = T::Currency::withdraw(
+                        &Self::account_id(),
+                        excess,
+                        WithdrawReasons::TRANSFER,
+                        ExistenceRequirement::AllowDeath,
+                    )?;
+                    Self::credit_treasury(excess_credit);
+                    Self::deposit_event(Event::RewardCapExcessCollected {
+                        market_id,
+                        amount: excess,
+                    });
                 }
             }
 
@@ -252,7 +719,9 @@ pub mod pallet {
                 ExistenceRequirement::AllowDeath,
             );
 
+            Self::note_participation(market_id);
             Markets::<T>::insert(market_id, market.clone());
+            Self::note_activity(|activity| activity.resolved.saturating_inc());
 
             Self::deposit_event(Event::RewardsDistributed {
                 market_id,
@@ -298,15 +767,835 @@ pub mod pallet {
 
             Ok(())
         }
-    }
 
-    impl<T: Config> Pallet<T> {
-        pub fn account_id() -> T::AccountId {
-            T::PalletId::get().into_account_truncating()
+        #[pallet::call_index(4)]
+        #[pallet::weight(10_000)]
+        pub fn flag_market(origin: OriginFor<T>, market_id: u32) -> DispatchResult {
+            T::GovernanceOrigin::ensure_origin(origin)?;
+            ensure!(
+                Markets::<T>::contains_key(market_id),
+                Error::<T>::MarketDoesNotExist
+            );
+
+            FlaggedMarkets::<T>::insert(market_id, ());
+
+            Self::deposit_event(Event::MarketFlagged { market_id });
+
+            Ok(())
+        }
+
+        #[pallet::call_index(5)]
+        #[pallet::weight(10_000)]
+        pub fn clear_market_flag(origin: OriginFor<T>, market_id: u32) -> DispatchResult {
+            T::GovernanceOrigin::ensure_origin(origin)?;
+            ensure!(
+                Markets::<T>::contains_key(market_id),
+                Error::<T>::MarketDoesNotExist
+            );
+
+            FlaggedMarkets::<T>::remove(market_id);
+
+            Self::deposit_event(Event::MarketFlagCleared { market_id });
+
+            Ok(())
+        }
+
+        #[pallet::call_index(6)]
+        #[pallet::weight(10_000)]
+        pub fn append_metadata(
+            origin: OriginFor<T>,
+            market_id: u32,
+            extra: Vec<u8>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let mut market = Markets::<T>::get(market_id).ok_or(Error::<T>::MarketDoesNotExist)?;
+            ensure!(market.creator == who, Error::<T>::NotMarketCreator);
+            ensure!(market.is_active, Error::<T>::MarketNotActive);
+            // Metadata is frozen once anyone has voted on it.
+            ensure!(
+                market.yes_votes == 0 && market.no_votes == 0,
+                Error::<T>::MetadataLocked
+            );
+
+            let mut metadata = market.metadata.into_inner();
+            metadata.extend(extra);
+            market.metadata = metadata
+                .try_into()
+                .map_err(|_| Error::<T>::MetadataTooLong)?;
+
+            Markets::<T>::insert(market_id, market.clone());
+
+            Self::deposit_event(Event::MetadataAppended {
+                market_id,
+                metadata: market.metadata,
+            });
+
+            Ok(())
+        }
+
+        #[pallet::call_index(7)]
+        #[pallet::weight(10_000)]
+        pub fn set_payout_beneficiary(
+            origin: OriginFor<T>,
+            beneficiary: T::AccountId,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            if beneficiary == who {
+                PayoutBeneficiaries::<T>::remove(&who);
+            } else {
+                PayoutBeneficiaries::<T>::insert(&who, &beneficiary);
+            }
+
+            Self::deposit_event(Event::PayoutBeneficiarySet { who, beneficiary });
+
+            Ok(())
+        }
+
+        #[pallet::call_index(8)]
+        #[pallet::weight(10_000)]
+        pub fn set_resolve_after(
+            origin: OriginFor<T>,
+            market_id: u32,
+            resolve_after: MomentOf<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let mut market = Markets::<T>::get(market_id).ok_or(Error::<T>::MarketDoesNotExist)?;
+            ensure!(market.creator == who, Error::<T>::NotMarketCreator);
+            ensure!(market.is_active, Error::<T>::MarketNotActive);
+            ensure!(
+                market.yes_votes == 0 && market.no_votes == 0,
+                Error::<T>::ResolveAfterLocked
+            );
+
+            market.resolve_after = Some(resolve_after);
+            Markets::<T>::insert(market_id, market);
+
+            Self::deposit_event(Event::ResolveAfterSet {
+                market_id,
+                resolve_after,
+            });
+
+            Ok(())
+        }
+
+        #[pallet::call_index(9)]
+        #[pallet::weight(10_000)]
+        pub fn governance_set_end_block(
+            origin: OriginFor<T>,
+            market_id: u32,
+            new_end_block: BlockNumberFor<T>,
+        ) -> DispatchResult {
+            T::GovernanceOrigin::ensure_origin(origin)?;
+
+            let mut market = Markets::<T>::get(market_id).ok_or(Error::<T>::MarketDoesNotExist)?;
+            ensure!(market.is_active, Error::<T>::MarketNotActive);
+
+            // Ending a market before now would strand votes already cast in it.
+            let has_votes = market.yes_votes > 0 || market.no_votes > 0;
+            ensure!(
+                new_end_block >= frame_system::Pallet::<T>::block_number()
+                    || !has_votes
+                    || T::AllowRetroactiveEndBlock::get(),
+                Error::<T>::EndBlockInPast
+            );
+
+            // A snapshot is taken relative to the end block, so moving it starts the snapshot
+            // over and lets every vote cast so far count again.
+            if new_end_block != market.end_block {
+                market.snapshot = None;
+                let _ = LateVotes::<T>::clear_prefix(market_id, u32::MAX, None);
+            }
+            market.end_block = new_end_block;
+            Markets::<T>::insert(market_id, market);
+
+            Self::deposit_event(Event::EndBlockSet {
+                market_id,
+                end_block: new_end_block,
+            });
+
+            Ok(())
+        }
+
+        #[pallet::call_index(11)]
+        #[pallet::weight(10_000)]
+        #[transactional]
+        pub fn create_and_vote(
+            origin: OriginFor<T>,
+            metadata: Vec<u8>,
+            vote_yes: bool,
+        ) -> DispatchResult {
+            let creator = ensure_signed(origin)?;
+
+            let market_id = Self::do_create_market(creator.clone(), metadata)?;
+            Self::do_vote(creator, market_id, vote_yes)?;
+
+            Ok(())
+        }
+
+        #[pallet::call_index(12)]
+        #[pallet::weight(10_000)]
+        pub fn register_keeper(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(!Keepers::<T>::contains_key(&who), Error::<T>::AlreadyKeeper);
+
+            let bond = T::KeeperBond::get();
+            T::Currency::reserve(&who, bond)?;
+            Keepers::<T>::insert(&who, (bond, None::<BlockNumberFor<T>>));
+
+            Self::deposit_event(Event::KeeperRegistered { who, bond });
+
+            Ok(())
+        }
+
+        #[pallet::call_index(13)]
+        #[pallet::weight(10_000)]
+        pub fn deregister_keeper(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let (bond, unbonding) = Keepers::<T>::get(&who).ok_or(Error::<T>::NotKeeper)?;
+            ensure!(unbonding.is_none(), Error::<T>::NotKeeper);
+
+            // The bond stays reserved, and slashable, until the unbonding period has passed.
+            let unbond_at = frame_system::Pallet::<T>::block_number()
+                .saturating_add(T::KeeperUnbondingPeriod::get().into());
+            Keepers::<T>::insert(&who, (bond, Some(unbond_at)));
+
+            Self::deposit_event(Event::KeeperDeregistered { who, unbond_at });
+
+            Ok(())
+        }
+
+        #[pallet::call_index(14)]
+        #[pallet::weight(10_000)]
+        pub fn slash_keeper(origin: OriginFor<T>, keeper: T::AccountId) -> DispatchResult {
+            T::GovernanceOrigin::ensure_origin(origin)?;
+            let (bond, unbond_at) = Keepers::<T>::get(&keeper).ok_or(Error::<T>::NotKeeper)?;
+            // A deregistered keeper can still be slashed until its bond unlocks.
+            let now = frame_system::Pallet::<T>::block_number();
+            ensure!(
+                unbond_at.map_or(true, |unbond_at| now < unbond_at),
+                Error::<T>::NotKeeper
+            );
+            Keepers::<T>::remove(&keeper);
+
+            let (slashed, unslashed) = T::Currency::slash_reserved(&keeper, bond);
+            Self::credit_treasury(slashed);
+
+            Self::deposit_event(Event::KeeperSlashed {
+                who: keeper,
+                amount: bond.saturating_sub(unslashed),
+            });
+
+            Ok(())
+        }
+
+        #[pallet::call_index(15)]
+        #[pallet::weight(10_000)]
+        pub fn withdraw_keeper_bond(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let (bond, unbond_at) = Keepers::<T>::get(&who).ok_or(Error::<T>::NotKeeper)?;
+            ensure!(
+                unbond_at.map_or(false, |unbond_at| {
+                    frame_system::Pallet::<T>::block_number() >= unbond_at
+                }),
+                Error::<T>::KeeperBondLocked
+            );
+
+            Keepers::<T>::remove(&who);
+            T::Currency::unreserve(&who, bond);
+
+            Self::deposit_event(Event::KeeperBondWithdrawn { who, amount: bond });
+
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        pub fn account_id() -> T::AccountId {
+            T::PalletId::get().into_account_truncating()
+        }
+
+        pub fn do_create_market(
+            creator: T::AccountId,
+            metadata: Vec<u8>,
+        ) -> Result<u32, DispatchError> {
+            let (bounded_metadata, deposit) = Self::check_create_market(&creator, metadata)?;
+
+            let market_id = MarketCount::<T>::get();
+            let end_block = frame_system::Pallet::<T>::block_number()
+                .saturating_add(T::MarketDuration::get().into());
+
+            MarketsByCreator::<T>::try_append(&creator, market_id)
+                .map_err(|_| Error::<T>::TooManyMarkets)?;
+            T::DepositStaker::stake(&creator, deposit)?;
+
+            let market = Market {
+                creator: creator.clone(),
+                end_block,
+                yes_votes: 0,
+                no_votes: 0,
+                total_staked: Zero::zero(),
+                is_active: true,
+                voting_closed: false,
+                resolve_after: None,
+                resolved_outcome: None,
+                resolved_at: None,
+                snapshot: None,
+                deposit,
+                metadata: bounded_metadata.clone(),
+                memes: BoundedVec::default(),
+            };
+
+            Markets::<T>::insert(market_id, market);
+            MarketCount::<T>::put(market_id.saturating_add(1));
+            Self::note_activity(|activity| activity.created.saturating_inc());
+
+            Self::deposit_event(Event::MarketCreated {
+                market_id,
+                creator,
+                end_block,
+                metadata: bounded_metadata,
+            });
+
+            Ok(market_id)
+        }
+
+        pub fn do_vote(
+            voter: T::AccountId,
+            market_id: u32,
+            vote_yes: bool,
+        ) -> Result<Pays, DispatchError> {
+            let mut market = Markets::<T>::get(market_id).ok_or(Error::<T>::MarketDoesNotExist)?;
+            let now = frame_system::Pallet::<T>::block_number();
+            ensure!(market.is_active, Error::<T>::MarketNotActive);
+            ensure!(now <= market.end_block, Error::<T>::MarketNotActive);
+            ensure!(!market.voting_closed, Error::<T>::VotingClosed);
+            ensure!(
+                !FlaggedMarkets::<T>::contains_key(market_id),
+                Error::<T>::MarketUnderReview
+            );
+            ensure!(
+                !Votes::<T>::contains_key(market_id, &voter),
+                Error::<T>::AlreadyVoted
+            );
+            ensure!(
+                T::AllowCreatorVoting::get() || market.creator != voter,
+                Error::<T>::CreatorCannotVote
+            );
+            ensure!(
+                T::VoterAttestation::is_attested(&voter),
+                Error::<T>::AttestationRequired
+            );
+            let cooldown = T::VoteCooldown::get();
+            if cooldown > 0 {
+                if let Some(last_voted_at) = LastVotedAt::<T>::get(&voter) {
+                    ensure!(
+                        now >= last_voted_at.saturating_add(cooldown.into()),
+                        Error::<T>::VoteCooldownActive
+                    );
+                }
+                LastVotedAt::<T>::insert(&voter, now);
+            }
+
+            let stake = Self::quote_vote_cost()?;
+            let freeze_from = market
+                .end_block
+                .saturating_sub(T::StakeFreezeWindow::get().into());
+            // The charge follows the price oracle, so late votes are rejected only while it
+            // quotes above the cap.
+            ensure!(
+                now <= freeze_from || stake <= T::LateStakeCap::get(),
+                Error::<T>::LateStakeTooLarge
+            );
+
+            // Seeding a brand-new market is fee-free while the voter has quota left.
+            let free_votes_used = FreeVotesUsed::<T>::get(&voter);
+            let pays_fee = if market.yes_votes == 0
+                && market.no_votes == 0
+                && free_votes_used < T::FreeVoteQuota::get()
+            {
+                FreeVotesUsed::<T>::insert(&voter, free_votes_used.saturating_add(1));
+                Pays::No
+            } else {
+                Pays::Yes
+            };
+
+            // Transfer vote cost
+            T::Currency::transfer(
+                &voter,
+                &Self::account_id(),
+                stake,
+                ExistenceRequirement::KeepAlive,
+            )?;
+            UserStats::<T>::mutate(&voter, |stats| {
+                stats.total_staked = stats.total_staked.saturating_add(stake);
+            });
+            VoteStakes::<T>::insert(market_id, &voter, stake);
+            LastVoteCharge::<T>::put(stake);
+
+            // The first vote past the snapshot block freezes the tallies it finds.
+            let snapshot_offset = T::SnapshotOffset::get();
+            let snapshot_block = market.end_block.saturating_sub(snapshot_offset.into());
+            if snapshot_offset > 0 && now > snapshot_block {
+                if market.snapshot.is_none() {
+                    market.snapshot = Some((market.yes_votes, market.no_votes));
+                }
+                LateVotes::<T>::insert(market_id, &voter, ());
+            }
+
+            if vote_yes {
+                market.yes_votes = market.yes_votes.saturating_add(1);
+            } else {
+                market.no_votes = market.no_votes.saturating_add(1);
+            }
+            market.total_staked = market.total_staked.saturating_add(stake);
+
+            // Bound the resolution loop by closing voting once the cap is reached.
+            let total_votes = market.yes_votes.saturating_add(market.no_votes);
+            market.voting_closed = total_votes >= T::MaxVotesPerMarket::get()
+                || (T::CloseWhenDecided::get() && Self::is_decided(&market));
+            let voting_closed = market.voting_closed;
+
+            Markets::<T>::insert(market_id, market);
+            Votes::<T>::insert(market_id, &voter, vote_yes);
+
+            Self::deposit_event(Event::VoteCast {
+                market_id,
+                voter,
+                vote: vote_yes,
+            });
+            if voting_closed {
+                Self::deposit_event(Event::VotingClosed { market_id });
+            }
+
+            Ok(pays_fee)
+        }
+
+        // Dry run of `create_market`, returning the first error it would fail with.
+        pub fn validate_create_market(creator: &T::AccountId, metadata: Vec<u8>) -> DispatchResult {
+            Self::check_create_market(creator, metadata).map(|_| ())
+        }
+
+        // Every check `create_market` performs before writing, without touching storage.
+        fn check_create_market(
+            creator: &T::AccountId,
+            metadata: Vec<u8>,
+        ) -> Result<(BoundedVec<u8, ConstU32<256>>, BalanceOf<T>), DispatchError> {
+            let bounded_metadata: BoundedVec<_, _> = metadata
+                .try_into()
+                .map_err(|_| Error::<T>::MetadataTooLong)?;
+
+            let created_markets = MarketsByCreator::<T>::decode_len(creator).unwrap_or(0);
+            ensure!(
+                created_markets < T::MaxMarketsPerCreator::get() as usize,
+                Error::<T>::TooManyMarkets
+            );
+
+            let deposit = T::DepositScaling::deposit_for(
+                T::CreationDeposit::get(),
+                Self::active_market_count(creator),
+            );
+            ensure!(
+                T::Currency::can_reserve(creator, deposit),
+                Error::<T>::InsufficientDeposit
+            );
+
+            Ok((bounded_metadata, deposit))
         }
 
         pub fn get_market_memes(market_id: u32) -> Option<Vec<Meme<T>>> {
             Markets::<T>::get(market_id).map(|market| market.memes.to_vec())
         }
+
+        pub fn creator_projected_reward(market_id: u32) -> BalanceOf<T> {
+            Markets::<T>::get(market_id)
+                .filter(|market| {
+                    let yes_wins = Self::yes_side_wins(Self::majority_outcome(market));
+                    let winners = Self::eligible_winners(market_id, yes_wins);
+                    Self::void_reason(market, &winners).is_none()
+                })
+                .map(|market| {
+                    let burned = T::BurnPercentage::get() * market.total_staked;
+                    Self::creator_reward(market.total_staked.saturating_sub(burned))
+                })
+                .unwrap_or_else(Zero::zero)
+        }
+
+        pub fn market_participation(market_id: u32) -> Option<(u32, u32, BalanceOf<T>)> {
+            Markets::<T>::get(market_id)
+                .map(|market| (market.yes_votes, market.no_votes, market.total_staked))
+        }
+
+        pub fn market_outcome(market_id: u32) -> Option<Outcome> {
+            Markets::<T>::get(market_id).and_then(|market| market.resolved_outcome)
+        }
+
+        // A stable numeric code per error for off-chain gateways. Codes are never reused, so new
+        // errors take the next free number.
+        pub fn error_code(error: &Error<T>) -> u16 {
+            match error {
+                Error::MarketDoesNotExist => 1,
+                Error::MarketNotActive => 2,
+                Error::MarketStillActive => 3,
+                Error::AlreadyVoted => 4,
+                Error::InvalidVoteCost => 5,
+                Error::MetadataTooLong => 6,
+                Error::MemeListFull => 7,
+                Error::InvalidCID => 8,
+                Error::AttestationRequired => 9,
+                Error::LateStakeTooLarge => 10,
+                Error::MarketUnderReview => 11,
+                Error::TooManyMarkets => 12,
+                Error::NotMarketCreator => 13,
+                Error::MetadataLocked => 14,
+                Error::InsufficientDeposit => 15,
+                Error::VotingClosed => 16,
+                Error::ResolveAfterLocked => 17,
+                Error::ReferenceTimeNotReached => 18,
+                Error::EndBlockInPast => 19,
+                Error::InsufficientPoolBalance => 20,
+                Error::VoteCooldownActive => 21,
+                Error::CreatorCannotVote => 22,
+                Error::AlreadyKeeper => 23,
+                Error::NotKeeper => 24,
+                Error::PriceUnavailable => 25,
+                Error::KeeperBondLocked => 26,
+                Error::__Ignore(_, never) => match *never {},
+            }
+        }
+
+        pub fn market_exists(market_id: u32) -> bool {
+            Markets::<T>::contains_key(market_id)
+        }
+
+        // Whether the market's timing now allows `release_rewards` to settle it.
+        pub fn is_resolvable(market_id: u32) -> bool {
+            Markets::<T>::get(market_id).map_or(false, |market| {
+                market.is_active
+                    && (market.voting_closed
+                        || frame_system::Pallet::<T>::block_number() > market.end_block)
+                    && market
+                        .resolve_after
+                        .map_or(true, |moment| T::ReferenceTime::now() >= moment)
+            })
+        }
+
+        pub fn export_outcome(
+            market_id: u32,
+        ) -> Option<OutcomeAttestation<T::Hash, BlockNumberFor<T>, BalanceOf<T>>> {
+            let market = Markets::<T>::get(market_id)?;
+            Some(OutcomeAttestation {
+                genesis_hash: frame_system::Pallet::<T>::block_hash(BlockNumberFor::<T>::zero()),
+                market_id,
+                outcome: market.resolved_outcome?,
+                end_block: market.end_block,
+                resolved_at: market.resolved_at?,
+                yes_votes: market.yes_votes,
+                no_votes: market.no_votes,
+                total_staked: market.total_staked,
+            })
+        }
+
+        // A local consistency check: whether the attestation names this chain and matches what
+        // it recorded at resolution. It proves nothing to another chain.
+        pub fn verify_outcome_attestation(
+            attestation: &OutcomeAttestation<T::Hash, BlockNumberFor<T>, BalanceOf<T>>,
+        ) -> bool {
+            Self::export_outcome(attestation.market_id).as_ref() == Some(attestation)
+        }
+
+        // The weight `who` contributes to its chosen side's tally at resolution. Every vote
+        // currently counts once regardless of stake, and votes cast after the snapshot not at all.
+        pub fn effective_vote_power(market_id: u32, who: &T::AccountId) -> u128 {
+            if Votes::<T>::contains_key(market_id, who)
+                && !LateVotes::<T>::contains_key(market_id, who)
+            {
+                1
+            } else {
+                0
+            }
+        }
+
+        // Lifetime `(total_staked, total_won)` for `who` across all markets.
+        pub fn account_pnl(who: &T::AccountId) -> (BalanceOf<T>, BalanceOf<T>) {
+            let stats = UserStats::<T>::get(who);
+            (stats.total_staked, stats.total_won)
+        }
+
+        pub fn market_odds(market_id: u32) -> Option<(Permill, Permill)> {
+            Markets::<T>::get(market_id)
+                .map(|market| Self::implied_odds(market.yes_votes, market.no_votes))
+        }
+
+        // Implied odds after a hypothetical vote, without touching storage. Each account votes
+        // once at the quoted charge, so the stake does not change how much the vote counts.
+        pub fn simulate_vote_impact(
+            market_id: u32,
+            vote_yes: bool,
+            _stake: BalanceOf<T>,
+        ) -> Option<(Permill, Permill)> {
+            Markets::<T>::get(market_id).map(|market| {
+                let (mut yes_votes, mut no_votes) = (market.yes_votes, market.no_votes);
+                if vote_yes {
+                    yes_votes.saturating_inc();
+                } else {
+                    no_votes.saturating_inc();
+                }
+                Self::implied_odds(yes_votes, no_votes)
+            })
+        }
+
+        // `VoteCost` in the native token, falling back to the last charge without a price.
+        fn quote_vote_cost() -> Result<BalanceOf<T>, DispatchError> {
+            T::PriceOracle::to_native(T::VoteCost::get())
+                .or_else(LastVoteCharge::<T>::get)
+                .ok_or_else(|| Error::<T>::PriceUnavailable.into())
+        }
+
+        // Each side's share of the votes as `(yes, no)`; zero for a market without votes.
+        fn implied_odds(yes_votes: u32, no_votes: u32) -> (Permill, Permill) {
+            let total = yes_votes.saturating_add(no_votes);
+            if total == 0 {
+                return (Permill::zero(), Permill::zero());
+            }
+            let yes = Permill::from_rational(yes_votes, total);
+            (yes, Permill::one().saturating_sub(yes))
+        }
+
+        pub fn payout_account(voter: &T::AccountId) -> T::AccountId {
+            PayoutBeneficiaries::<T>::get(voter).unwrap_or_else(|| voter.clone())
+        }
+
+        pub fn active_market_count(creator: &T::AccountId) -> u32 {
+            MarketsByCreator::<T>::get(creator)
+                .iter()
+                .filter(|market_id| {
+                    Markets::<T>::get(**market_id).map_or(false, |market| market.is_active)
+                })
+                .count() as u32
+        }
+
+        fn escalate_overdue_markets(now: BlockNumberFor<T>) -> Weight {
+            let market_count = MarketCount::<T>::get();
+            let checks = T::MaxEscalationChecksPerBlock::get().min(market_count);
+            if checks == 0 {
+                return T::DbWeight::get().reads(1);
+            }
+
+            // Walk market ids round-robin so every market is revisited within a bounded cost.
+            let mut cursor = EscalationCursor::<T>::get();
+            let mut queue = GovernanceResolutionQueue::<T>::get();
+            let mut escalated = 0u32;
+            for _ in 0..checks {
+                let market_id = cursor % market_count;
+                cursor = market_id.saturating_add(1);
+
+                let Some(market) = Markets::<T>::get(market_id) else {
+                    continue;
+                };
+                let escalate_after = market
+                    .end_block
+                    .saturating_add(T::EscalationDelay::get().into());
+                if market.is_active && now > escalate_after && !queue.contains(&market_id) {
+                    if queue.try_push(market_id).is_err() {
+                        break;
+                    }
+                    escalated = escalated.saturating_add(1);
+                    Self::deposit_event(Event::MarketEscalated { market_id });
+                }
+            }
+
+            EscalationCursor::<T>::put(cursor);
+            if escalated > 0 {
+                GovernanceResolutionQueue::<T>::put(queue);
+            }
+
+            T::DbWeight::get().reads_writes(u64::from(checks) + 3, u64::from(escalated.min(1)) + 1)
+        }
+
+        // Pays the resolution tip if `resolver` is a registered keeper that has not deregistered
+        // and the treasury can cover it.
+        fn tip_keeper(resolver: &T::AccountId, market_id: u32) {
+            let tip = T::KeeperTip::get();
+            let is_active_keeper = matches!(Keepers::<T>::get(resolver), Some((_, None)));
+            if tip.is_zero() || !is_active_keeper {
+                return;
+            }
+            let paid = T::Currency::transfer(
+                &T::TreasuryAccount::get(),
+                resolver,
+                tip,
+                ExistenceRequirement::KeepAlive,
+            );
+            if paid.is_ok() {
+                Self::deposit_event(Event::KeeperTipPaid {
+                    keeper: resolver.clone(),
+                    market_id,
+                    amount: tip,
+                });
+            }
+        }
+
+        // Bumps this block's summary counters when the summary event is enabled.
+        fn note_activity(record: impl FnOnce(&mut BlockActivityCounters)) {
+            if T::EmitBlockSummary::get() {
+                BlockActivity::<T>::mutate(record);
+            }
+        }
+
+        // Settles whatever the staker hands back, yield included, according to `policy`.
+        fn settle_deposit(market: &Market<T>, policy: DepositPolicy) {
+            let returned = T::DepositStaker::unstake(&market.creator, market.deposit);
+            match policy {
+                DepositPolicy::Return => {
+                    T::Currency::resolve_creating(&market.creator, returned);
+                }
+                DepositPolicy::Slash => Self::credit_treasury(returned),
+                // Dropping the imbalance reduces total issuance.
+                DepositPolicy::Burn => drop(returned),
+            }
+        }
+
+        // Credits the treasury, creating its account if needed. An amount too small to open the
+        // account is burned rather than left stranded.
+        fn credit_treasury(amount: NegativeImbalanceOf<T>) {
+            T::Currency::resolve_creating(&T::TreasuryAccount::get(), amount);
+        }
+
+        // The tallies that decide the outcome: the snapshot when one was taken.
+        fn resolution_tallies(market: &Market<T>) -> (u32, u32) {
+            market
+                .snapshot
+                .unwrap_or((market.yes_votes, market.no_votes))
+        }
+
+        // Why the market would be voided, given the eligible voters on its winning side.
+        fn void_reason(market: &Market<T>, winners: &[T::AccountId]) -> Option<VoidReason> {
+            let (yes_votes, no_votes) = Self::resolution_tallies(market);
+            if yes_votes.abs_diff(no_votes) < T::MinWinMargin::get() {
+                return Some(VoidReason::MarginNotMet);
+            }
+            if yes_votes.max(no_votes) < T::MinWinnersToPay::get() {
+                return Some(VoidReason::TooFewWinners);
+            }
+            if winners.is_empty() {
+                return Some(VoidReason::NoEligibleWinners);
+            }
+            None
+        }
+
+        fn eligible_winners(market_id: u32, yes_wins: bool) -> Vec<T::AccountId> {
+            Votes::<T>::iter_prefix(market_id)
+                .filter(|(voter, vote)| Self::is_payable_winner(market_id, voter, *vote, yes_wins))
+                .map(|(voter, _)| voter)
+                .collect()
+        }
+
+        fn is_payable_winner(
+            market_id: u32,
+            voter: &T::AccountId,
+            vote: bool,
+            yes_wins: bool,
+        ) -> bool {
+            vote == yes_wins
+                && !LateVotes::<T>::contains_key(market_id, voter)
+                && T::WinnerEligibility::is_eligible_winner(voter)
+        }
+
+        // Whether the leader wins, clear of the void rules, however the remaining capped votes
+        // fall.
+        fn is_decided(market: &Market<T>) -> bool {
+            let cast = market.yes_votes.saturating_add(market.no_votes);
+            let remaining = T::MaxVotesPerMarket::get().saturating_sub(cast);
+            let leader = market.yes_votes.max(market.no_votes);
+            let trailing = market.yes_votes.min(market.no_votes);
+            let worst_margin = leader.saturating_sub(trailing.saturating_add(remaining));
+            worst_margin >= T::MinWinMargin::get().max(1) && leader >= T::MinWinnersToPay::get()
+        }
+
+        fn majority_outcome(market: &Market<T>) -> Outcome {
+            let (yes_votes, no_votes) = Self::resolution_tallies(market);
+            match yes_votes.cmp(&no_votes) {
+                core::cmp::Ordering::Greater => Outcome::Yes,
+                core::cmp::Ordering::Less => Outcome::No,
+                core::cmp::Ordering::Equal => Outcome::Tie,
+            }
+        }
+
+        // A tie is recorded as `Outcome::Tie` but pays the No side, as resolution always has.
+        fn yes_side_wins(outcome: Outcome) -> bool {
+            outcome == Outcome::Yes
+        }
+
+        // Returns every voter's stake when a market is voided.
+        fn refund_voters(market_id: u32) {
+            for (voter, _) in Votes::<T>::iter_prefix(market_id) {
+                let stake =
+                    VoteStakes::<T>::get(market_id, &voter).unwrap_or_else(T::VoteCost::get);
+                Self::pay_voter(&voter, &voter, stake);
+            }
+        }
+
+        // Counts the market toward each of its voters' resolved-market history.
+        fn note_participation(market_id: u32) {
+            for (voter, _) in Votes::<T>::iter_prefix(market_id) {
+                UserStats::<T>::mutate(&voter, |stats| stats.markets_resolved.saturating_inc());
+            }
+        }
+
+        // Pays the treasury-funded bonus to a winner with enough prior resolved markets.
+        fn pay_loyalty_bonus(market_id: u32, voter: &T::AccountId, dest: &T::AccountId) {
+            let bonus = T::LoyaltyBonus::get();
+            if bonus.is_zero()
+                || UserStats::<T>::get(voter).markets_resolved < T::LoyaltyThreshold::get()
+            {
+                return;
+            }
+            let paid = T::Currency::transfer(
+                &T::TreasuryAccount::get(),
+                dest,
+                bonus,
+                ExistenceRequirement::KeepAlive,
+            );
+            if paid.is_ok() {
+                UserStats::<T>::mutate(voter, |stats| {
+                    stats.total_won = stats.total_won.saturating_add(bonus);
+                });
+                Self::deposit_event(Event::LoyaltyBonusPaid {
+                    market_id,
+                    who: voter.clone(),
+                    amount: bonus,
+                });
+            }
+        }
+
+        // Pays `amount` from the pool to `dest` and credits it to `voter`'s lifetime winnings.
+        fn pay_voter(voter: &T::AccountId, dest: &T::AccountId, amount: BalanceOf<T>) {
+            let paid = T::Currency::transfer(
+                &Self::account_id(),
+                dest,
+                amount,
+                ExistenceRequirement::AllowDeath,
+            );
+            if paid.is_ok() {
+                UserStats::<T>::mutate(voter, |stats| {
+                    stats.total_won = stats.total_won.saturating_add(amount);
+                });
+            }
+        }
+
+        // The creator's cut of a reward pool; shared by resolution and projections.
+        fn creator_reward(total_reward_pool: BalanceOf<T>) -> BalanceOf<T> {
+            let percentage = T::CreatorRewardCurve::creator_percentage(
+                T::CreatorRewardPercentage::get(),
+                total_reward_pool,
+            );
+            total_reward_pool
+                .saturating_mul(percentage.into())
+                .checked_div(&BalanceOf::<T>::from(100u32))
+                .unwrap_or_else(Zero::zero)
+        }
     }
 }
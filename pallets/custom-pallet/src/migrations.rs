@@ -0,0 +1,153 @@
+use crate::{Config, Market, Markets, MarketsByCreator, Pallet};
+use core::marker::PhantomData;
+use frame_support::migrations::VersionedMigration;
+use frame_support::pallet_prelude::*;
+use frame_support::sp_runtime::traits::Zero;
+use frame_support::traits::{Currency, UncheckedOnRuntimeUpgrade};
+use scale_info::prelude::collections::BTreeMap;
+use scale_info::prelude::vec::Vec;
+
+#[cfg(feature = "try-runtime")]
+use frame_support::sp_runtime::TryRuntimeError;
+
+type BalanceOf<T> =
+    <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+pub mod v0 {
+    use super::*;
+    use crate::Meme;
+    use frame_system::pallet_prelude::BlockNumberFor;
+
+    /// `Market` as stored before storage version 1, ahead of deposits, early closing,
+    /// reference-time locks, recorded outcomes and snapshots.
+    #[derive(Encode, Decode)]
+    pub struct Market<T: Config> {
+        pub creator: T::AccountId,
+        pub end_block: BlockNumberFor<T>,
+        pub yes_votes: u32,
+        pub no_votes: u32,
+        pub total_staked: BalanceOf<T>,
+        pub is_active: bool,
+        pub metadata: BoundedVec<u8, ConstU32<256>>,
+        pub memes: BoundedVec<Meme<T>, ConstU32<100>>,
+    }
+}
+
+pub mod v1 {
+    use super::*;
+
+    /// Moves every `Market` to the version 1 layout, then rebuilds `MarketsByCreator` from the
+    /// active markets for chains that created markets before the index existed.
+    pub struct BackfillCreatorIndex<T>(PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for BackfillCreatorIndex<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut translated = 0u64;
+            let mut by_creator: BTreeMap<T::AccountId, Vec<u32>> = BTreeMap::new();
+            Markets::<T>::translate::<v0::Market<T>, _>(|market_id, old| {
+                translated += 1;
+                if old.is_active {
+                    by_creator
+                        .entry(old.creator.clone())
+                        .or_default()
+                        .push(market_id);
+                }
+                // Markets created before deposits existed reserved nothing.
+                Some(Market {
+                    creator: old.creator,
+                    end_block: old.end_block,
+                    yes_votes: old.yes_votes,
+                    no_votes: old.no_votes,
+                    total_staked: old.total_staked,
+                    is_active: old.is_active,
+                    voting_closed: false,
+                    resolve_after: None,
+                    resolved_outcome: None,
+                    resolved_at: None,
+                    snapshot: None,
+                    deposit: Zero::zero(),
+                    metadata: old.metadata,
+                    memes: old.memes,
+                })
+            });
+
+            // Resolution drops markets from the index, so only active markets belong in it.
+            let _ = MarketsByCreator::<T>::clear(u32::MAX, None);
+            let writes = by_creator.len() as u64;
+            for (creator, mut market_ids) in by_creator {
+                // Keep the most recent active markets when a creator has more than the index
+                // holds.
+                market_ids.sort_unstable();
+                let keep_from = market_ids
+                    .len()
+                    .saturating_sub(T::MaxMarketsPerCreator::get() as usize);
+                let index = BoundedVec::truncate_from(market_ids.split_off(keep_from));
+                MarketsByCreator::<T>::insert(creator, index);
+            }
+
+            // Clearing the old index is approximated as one write per market.
+            T::DbWeight::get().reads_writes(translated, 2 * translated + writes)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+            let mut market_count = 0u32;
+            for market_id in Markets::<T>::iter_keys() {
+                let key = Markets::<T>::hashed_key_for(market_id);
+                ensure!(
+                    frame_support::storage::unhashed::get::<v0::Market<T>>(&key).is_some(),
+                    "market does not decode in the version 0 layout"
+                );
+                market_count += 1;
+            }
+            Ok(market_count.encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+            let market_count = u32::decode(&mut &state[..])
+                .map_err(|_| TryRuntimeError::Other("invalid pre-upgrade state"))?;
+
+            // `iter` skips values that fail to decode, so check every key decodes explicitly.
+            let mut decoded = 0u32;
+            let max_per_creator = T::MaxMarketsPerCreator::get() as usize;
+            for market_id in Markets::<T>::iter_keys() {
+                let market = Markets::<T>::try_get(market_id)
+                    .map_err(|_| TryRuntimeError::Other("market does not decode after upgrade"))?;
+                decoded += 1;
+
+                let index = MarketsByCreator::<T>::get(&market.creator);
+                ensure!(
+                    !market.is_active
+                        || index.contains(&market_id)
+                        || index.len() == max_per_creator,
+                    "active market missing from its creator's index"
+                );
+            }
+            ensure!(
+                decoded == market_count,
+                "markets changed during the creator index backfill"
+            );
+
+            for (_, market_ids) in MarketsByCreator::<T>::iter() {
+                for market_id in market_ids {
+                    ensure!(
+                        Markets::<T>::get(market_id).map_or(false, |market| market.is_active),
+                        "creator index refers to an inactive market"
+                    );
+                }
+            }
+            Ok(())
+        }
+    }
+
+    /// Migrates markets and backfills the creator index once, moving the pallet from storage
+    /// version 0 to 1.
+    pub type MigrateToV1<T> = VersionedMigration<
+        0,
+        1,
+        BackfillCreatorIndex<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
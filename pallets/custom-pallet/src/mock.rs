@@ -0,0 +1,269 @@
+use crate::traits::{
+    AttestationProvider, CreatorRewardCurve, DepositStaker, IsEligibleWinner, LinearDepositScaling,
+    PriceOracle,
+};
+use crate::DepositPolicy;
+use frame_support::{
+    derive_impl,
+    dispatch::DispatchResult,
+    parameter_types,
+    traits::{
+        ConstU32, ConstU64, Currency, ExistenceRequirement, Imbalance, ReservableCurrency, Time,
+        WithdrawReasons,
+    },
+    PalletId,
+};
+use frame_system::{mocking::MockBlock, EnsureRoot};
+use sp_runtime::{traits::BlakeTwo256, BuildStorage, Permill};
+
+pub const ALICE: u64 = 1;
+pub const BOB: u64 = 2;
+pub const CHARLIE: u64 = 3;
+pub const DAVE: u64 = 4;
+/// An account the mock attestation provider refuses to verify.
+pub const UNVERIFIED: u64 = 9;
+pub const TREASURY: u64 = 99;
+/// Where the mock deposit staker draws its yield from.
+pub const YIELD_SOURCE: u64 = 98;
+
+pub const VOTE_COST: u64 = 10;
+pub const MARKET_DURATION: u32 = 10;
+pub const STAKE_FREEZE_WINDOW: u32 = 3;
+pub const CREATION_DEPOSIT: u64 = 5;
+pub const MAX_MARKETS_PER_CREATOR: u32 = 4;
+pub const FREE_VOTE_QUOTA: u32 = 2;
+pub const ESCALATION_DELAY: u32 = 5;
+pub const KEEPER_BOND: u64 = 20;
+pub const KEEPER_TIP: u64 = 3;
+pub const KEEPER_UNBONDING_PERIOD: u32 = 5;
+pub const REGRESSIVE_POOL_THRESHOLD: u64 = 3 * VOTE_COST;
+
+#[frame_support::runtime]
+mod test_runtime {
+    #[runtime::runtime]
+    #[runtime::derive(
+        RuntimeCall,
+        RuntimeEvent,
+        RuntimeError,
+        RuntimeOrigin,
+        RuntimeFreezeReason,
+        RuntimeHoldReason,
+        RuntimeSlashReason,
+        RuntimeLockId,
+        RuntimeTask
+    )]
+    pub struct Test;
+
+    #[runtime::pallet_index(0)]
+    pub type System = frame_system;
+    #[runtime::pallet_index(1)]
+    pub type Balances = pallet_balances;
+    #[runtime::pallet_index(2)]
+    pub type CustomPallet = crate;
+    #[runtime::pallet_index(3)]
+    pub type Proxy = pallet_proxy;
+}
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+    type Block = MockBlock<Test>;
+    type AccountData = pallet_balances::AccountData<u64>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Test {
+    type AccountStore = System;
+}
+
+impl pallet_proxy::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type RuntimeCall = RuntimeCall;
+    type Currency = Balances;
+    type ProxyType = ();
+    type ProxyDepositBase = ConstU64<1>;
+    type ProxyDepositFactor = ConstU64<1>;
+    type MaxProxies = ConstU32<4>;
+    type WeightInfo = ();
+    type MaxPending = ConstU32<2>;
+    type CallHasher = BlakeTwo256;
+    type AnnouncementDepositBase = ConstU64<1>;
+    type AnnouncementDepositFactor = ConstU64<1>;
+}
+
+pub struct MockAttestation;
+impl AttestationProvider<u64> for MockAttestation {
+    fn is_attested(who: &u64) -> bool {
+        *who != UNVERIFIED
+    }
+}
+
+/// Halves the creator percentage for pools of at least `REGRESSIVE_POOL_THRESHOLD` when
+/// `RegressiveCreatorReward` is enabled.
+pub struct MockCreatorRewardCurve;
+impl CreatorRewardCurve<u64> for MockCreatorRewardCurve {
+    fn creator_percentage(base_percentage: u32, total_pool: u64) -> u32 {
+        if RegressiveCreatorReward::get() && total_pool >= REGRESSIVE_POOL_THRESHOLD {
+            base_percentage / 2
+        } else {
+            base_percentage
+        }
+    }
+}
+
+/// Refuses payouts to accounts listed in `IneligibleWinners`, counting calls in
+/// `EligibilityChecks`.
+pub struct MockWinnerEligibility;
+impl IsEligibleWinner<u64> for MockWinnerEligibility {
+    fn is_eligible_winner(who: &u64) -> bool {
+        EligibilityChecks::mutate(|checks| *checks += 1);
+        !IneligibleWinners::get().contains(who)
+    }
+}
+
+/// Reserves deposits and returns them with `DepositYield` taken from `YIELD_SOURCE`.
+pub struct MockDepositStaker;
+impl DepositStaker<u64, Balances> for MockDepositStaker {
+    fn stake(who: &u64, amount: u64) -> DispatchResult {
+        Balances::reserve(who, amount)
+    }
+
+    fn unstake(who: &u64, amount: u64) -> pallet_balances::NegativeImbalance<Test> {
+        let (principal, _) = Balances::slash_reserved(who, amount);
+        let accrued = Balances::withdraw(
+            &YIELD_SOURCE,
+            DepositYield::get(),
+            WithdrawReasons::TRANSFER,
+            ExistenceRequirement::AllowDeath,
+        )
+        .unwrap_or_else(|_| Imbalance::zero());
+        principal.merge(accrued)
+    }
+}
+
+/// Prices `VoteCost` at `OraclePrice` native units each; `None` models an outage.
+pub struct MockPriceOracle;
+impl PriceOracle<u64> for MockPriceOracle {
+    fn to_native(amount: u64) -> Option<u64> {
+        OraclePrice::get().map(|price| amount * price)
+    }
+}
+
+/// Reference clock driven by tests through `Now`.
+pub struct MockTime;
+impl Time for MockTime {
+    type Moment = u64;
+
+    fn now() -> u64 {
+        Now::get()
+    }
+}
+
+parameter_types! {
+    pub static Now: u64 = 0;
+    pub const PredictionMarketPalletId: PalletId = PalletId(*b"pred/mkt");
+    pub const VoteCost: u64 = VOTE_COST;
+    pub const CreatorRewardPercentage: u32 = 10;
+    pub const MarketDuration: u32 = MARKET_DURATION;
+    pub const StakeFreezeWindow: u32 = STAKE_FREEZE_WINDOW;
+    pub static LateStakeCap: u64 = u64::MAX;
+    pub static MinWinMargin: u32 = 0;
+    pub const CreationDeposit: u64 = CREATION_DEPOSIT;
+    pub const MaxMarketsPerCreator: u32 = MAX_MARKETS_PER_CREATOR;
+    pub static MinWinnersToPay: u32 = 0;
+    pub static RegressiveCreatorReward: bool = false;
+    pub static DepositOnVoid: DepositPolicy = DepositPolicy::Return;
+    pub const TreasuryAccount: u64 = TREASURY;
+    pub static MaxVotesPerMarket: u32 = 100;
+    pub const FreeVoteQuota: u32 = FREE_VOTE_QUOTA;
+    pub static BurnPercentage: Permill = Permill::zero();
+    pub static AllowRetroactiveEndBlock: bool = false;
+    pub static HousePercentage: Permill = Permill::zero();
+    pub const EscalationDelay: u32 = ESCALATION_DELAY;
+    pub const MaxEscalationChecksPerBlock: u32 = 2;
+    pub const MaxEscalatedMarkets: u32 = 4;
+    pub static IneligibleWinners: Vec<u64> = Vec::new();
+    pub static EligibilityChecks: u32 = 0;
+    pub static SnapshotOffset: u32 = 0;
+    pub static EmitBlockSummary: bool = false;
+    pub static VoteCooldown: u32 = 0;
+    pub static AllowCreatorVoting: bool = true;
+    pub static DepositYield: u64 = 0;
+    pub const KeeperBond: u64 = KEEPER_BOND;
+    pub const KeeperTip: u64 = KEEPER_TIP;
+    pub const KeeperUnbondingPeriod: u32 = KEEPER_UNBONDING_PERIOD;
+    pub static OraclePrice: Option<u64> = Some(1);
+    pub static MaxRewardPerAccount: u64 = u64::MAX;
+    pub static CloseWhenDecided: bool = false;
+    pub const LoyaltyThreshold: u32 = 1;
+    pub static LoyaltyBonus: u64 = 0;
+}
+
+impl crate::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type Currency = Balances;
+    type PalletId = PredictionMarketPalletId;
+    type VoteCost = VoteCost;
+    type CreatorRewardPercentage = CreatorRewardPercentage;
+    type MarketDuration = MarketDuration;
+    type VoterAttestation = MockAttestation;
+    type StakeFreezeWindow = StakeFreezeWindow;
+    type LateStakeCap = LateStakeCap;
+    type GovernanceOrigin = EnsureRoot<u64>;
+    type MinWinMargin = MinWinMargin;
+    type CreationDeposit = CreationDeposit;
+    type DepositScaling = LinearDepositScaling;
+    type DepositStaker = MockDepositStaker;
+    type MaxMarketsPerCreator = MaxMarketsPerCreator;
+    type MinWinnersToPay = MinWinnersToPay;
+    type CreatorRewardCurve = MockCreatorRewardCurve;
+    type DepositOnVoid = DepositOnVoid;
+    type TreasuryAccount = TreasuryAccount;
+    type MaxVotesPerMarket = MaxVotesPerMarket;
+    type FreeVoteQuota = FreeVoteQuota;
+    type ReferenceTime = MockTime;
+    type BurnPercentage = BurnPercentage;
+    type AllowRetroactiveEndBlock = AllowRetroactiveEndBlock;
+    type HousePercentage = HousePercentage;
+    type EscalationDelay = EscalationDelay;
+    type MaxEscalationChecksPerBlock = MaxEscalationChecksPerBlock;
+    type MaxEscalatedMarkets = MaxEscalatedMarkets;
+    type WinnerEligibility = MockWinnerEligibility;
+    type SnapshotOffset = SnapshotOffset;
+    type EmitBlockSummary = EmitBlockSummary;
+    type VoteCooldown = VoteCooldown;
+    type AllowCreatorVoting = AllowCreatorVoting;
+    type KeeperBond = KeeperBond;
+    type KeeperTip = KeeperTip;
+    type KeeperUnbondingPeriod = KeeperUnbondingPeriod;
+    type PriceOracle = MockPriceOracle;
+    type MaxRewardPerAccount = MaxRewardPerAccount;
+    type CloseWhenDecided = CloseWhenDecided;
+    type LoyaltyThreshold = LoyaltyThreshold;
+    type LoyaltyBonus = LoyaltyBonus;
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut t = frame_system::GenesisConfig::<Test>::default()
+        .build_storage()
+        .unwrap();
+    pallet_balances::GenesisConfig::<Test> {
+        balances: vec![
+            (ALICE, 1_000),
+            (BOB, 1_000),
+            (CHARLIE, 1_000),
+            (DAVE, 1_000),
+            (UNVERIFIED, 1_000),
+            (YIELD_SOURCE, 1_000),
+        ],
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(t);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
+
+pub fn run_to_block(n: u64) {
+    System::set_block_number(n);
+}
@@ -0,0 +1,56 @@
+use crate::{Outcome, OutcomeAttestation};
+use codec::Codec;
+use frame_support::sp_runtime::{DispatchError, Permill};
+use scale_info::prelude::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+    /// Read-only prediction market queries for front-ends and dashboards.
+    pub trait PredictionMarketApi<AccountId, Balance, BlockNumber, Hash>
+    where
+        AccountId: Codec,
+        Balance: Codec,
+        BlockNumber: Codec,
+        Hash: Codec,
+    {
+        /// The creator cut the market would pay out if it resolved with its current tallies.
+        fn creator_projected_reward(market_id: u32) -> Balance;
+
+        /// `(yes_votes, no_votes, total_staked)` for the market, if it exists.
+        fn market_participation(market_id: u32) -> Option<(u32, u32, Balance)>;
+
+        /// The definitive outcome recorded when the market was resolved.
+        fn market_outcome(market_id: u32) -> Option<Outcome>;
+
+        /// An unsigned summary of the market's resolution, if it has been resolved.
+        fn export_outcome(market_id: u32) -> Option<OutcomeAttestation<Hash, BlockNumber, Balance>>;
+
+        /// Whether the attestation names this chain and matches the resolution recorded here.
+        /// A local consistency check, not a cross-chain proof.
+        fn verify_outcome_attestation(
+            attestation: OutcomeAttestation<Hash, BlockNumber, Balance>,
+        ) -> bool;
+
+        /// Runs every `create_market` check for `creator` without creating the market.
+        fn validate_create_market(creator: AccountId, metadata: Vec<u8>) -> Result<(), DispatchError>;
+
+        /// The weight `who` contributes to its chosen side when the market resolves.
+        fn effective_vote_power(market_id: u32, who: AccountId) -> u128;
+
+        /// `(yes, no)` share of the votes after one more vote for the chosen side. Each account
+        /// votes once, so `stake` does not change the result.
+        fn simulate_vote_impact(
+            market_id: u32,
+            vote_yes: bool,
+            stake: Balance,
+        ) -> Option<(Permill, Permill)>;
+
+        /// Lifetime `(total_staked, total_won)` for `who` across all markets.
+        fn account_pnl(who: AccountId) -> (Balance, Balance);
+
+        /// Whether a market with this id has been created and not pruned.
+        fn market_exists(market_id: u32) -> bool;
+
+        /// Whether `release_rewards` could settle the market now.
+        fn is_resolvable(market_id: u32) -> bool;
+    }
+}
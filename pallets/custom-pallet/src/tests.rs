@@ -0,0 +1,2179 @@
+use crate::migrations::v1::MigrateToV1;
+use crate::{
+    mock::*, DepositPolicy, Error, Event, FlaggedMarkets, FreeVotesUsed, GovernanceResolutionQueue,
+    Markets, MarketsByCreator, Outcome, PayoutBeneficiaries, VoidReason, Votes,
+};
+use frame_support::{
+    assert_noop, assert_ok,
+    dispatch::Pays,
+    traits::{Currency, GetStorageVersion, Hooks, OnRuntimeUpgrade, StorageVersion},
+    BoundedVec,
+};
+use sp_runtime::{DispatchError, Permill};
+
+fn vote(voter: u64, market_id: u32, vote_yes: bool) {
+    assert_ok!(CustomPallet::vote(
+        RuntimeOrigin::signed(voter),
+        market_id,
+        vote_yes
+    ));
+}
+
+fn end_market(market_id: u32) {
+    let end_block = Markets::<Test>::get(market_id).unwrap().end_block;
+    run_to_block(end_block + 1);
+}
+
+fn create_market(creator: u64) -> u32 {
+    let market_id = crate::MarketCount::<Test>::get();
+    assert_ok!(CustomPallet::create_market(
+        RuntimeOrigin::signed(creator),
+        b"will it rain?".to_vec()
+    ));
+    market_id
+}
+
+#[test]
+fn attested_voter_can_vote() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_market(ALICE);
+
+        assert_ok!(CustomPallet::vote(
+            RuntimeOrigin::signed(BOB),
+            market_id,
+            true
+        ));
+
+        assert!(Votes::<Test>::get(market_id, BOB));
+        assert_eq!(Markets::<Test>::get(market_id).unwrap().yes_votes, 1);
+        System::assert_last_event(
+            Event::VoteCast {
+                market_id,
+                voter: BOB,
+                vote: true,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn vote_without_attestation_fails() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_market(ALICE);
+
+        assert_noop!(
+            CustomPallet::vote(RuntimeOrigin::signed(UNVERIFIED), market_id, true),
+            Error::<Test>::AttestationRequired
+        );
+        assert_eq!(Balances::free_balance(UNVERIFIED), 1_000);
+    });
+}
+
+#[test]
+fn large_stake_is_rejected_inside_freeze_window() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_market(ALICE);
+        let end_block = Markets::<Test>::get(market_id).unwrap().end_block;
+        LateStakeCap::set(VOTE_COST - 1);
+
+        run_to_block(end_block - STAKE_FREEZE_WINDOW as u64 + 1);
+        assert_noop!(
+            CustomPallet::vote(RuntimeOrigin::signed(BOB), market_id, true),
+            Error::<Test>::LateStakeTooLarge
+        );
+
+        run_to_block(end_block);
+        assert_noop!(
+            CustomPallet::vote(RuntimeOrigin::signed(BOB), market_id, true),
+            Error::<Test>::LateStakeTooLarge
+        );
+    });
+}
+
+#[test]
+fn large_stake_is_allowed_before_freeze_window() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_market(ALICE);
+        let end_block = Markets::<Test>::get(market_id).unwrap().end_block;
+        LateStakeCap::set(VOTE_COST - 1);
+
+        run_to_block(end_block - STAKE_FREEZE_WINDOW as u64);
+        vote(BOB, market_id, true);
+    });
+}
+
+#[test]
+fn small_stake_is_allowed_inside_freeze_window() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_market(ALICE);
+        let end_block = Markets::<Test>::get(market_id).unwrap().end_block;
+        LateStakeCap::set(VOTE_COST);
+
+        run_to_block(end_block);
+        vote(BOB, market_id, true);
+    });
+}
+
+#[test]
+fn late_stake_cap_applies_to_each_votes_quoted_charge() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_market(ALICE);
+        let end_block = Markets::<Test>::get(market_id).unwrap().end_block;
+        LateStakeCap::set(2 * VOTE_COST);
+
+        run_to_block(end_block);
+        OraclePrice::set(Some(3));
+        assert_noop!(
+            CustomPallet::vote(RuntimeOrigin::signed(BOB), market_id, true),
+            Error::<Test>::LateStakeTooLarge
+        );
+        OraclePrice::set(Some(2));
+        vote(BOB, market_id, true);
+        vote(CHARLIE, market_id, false);
+    });
+}
+
+#[test]
+fn creator_projection_matches_reward_at_resolution() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_market(ALICE);
+        assert_ok!(CustomPallet::vote(
+            RuntimeOrigin::signed(BOB),
+            market_id,
+            true
+        ));
+        assert_ok!(CustomPallet::vote(
+            RuntimeOrigin::signed(CHARLIE),
+            market_id,
+            true
+        ));
+        assert_ok!(CustomPallet::vote(
+            RuntimeOrigin::signed(DAVE),
+            market_id,
+            false
+        ));
+
+        let projected = CustomPallet::creator_projected_reward(market_id);
+        assert_eq!(projected, 3 * VOTE_COST / 10);
+        assert_eq!(
+            CustomPallet::market_participation(market_id),
+            Some((2, 1, 3 * VOTE_COST))
+        );
+
+        end_market(market_id);
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            market_id
+        ));
+
+        System::assert_last_event(
+            Event::RewardsDistributed {
+                market_id,
+                creator: ALICE,
+                creator_reward: projected,
+            }
+            .into(),
+        );
+
+        // A tie still pays out, so the creator is rewarded as projected.
+        let tied = create_market(ALICE);
+        vote(BOB, tied, true);
+        vote(CHARLIE, tied, false);
+        let projected = CustomPallet::creator_projected_reward(tied);
+        assert_eq!(projected, 2 * VOTE_COST / 10);
+        end_market(tied);
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            tied
+        ));
+        System::assert_last_event(
+            Event::RewardsDistributed {
+                market_id: tied,
+                creator: ALICE,
+                creator_reward: projected,
+            }
+            .into(),
+        );
+
+        // A market that will be voided pays its creator nothing.
+        MinWinMargin::set(2);
+        let voided = create_market(ALICE);
+        vote(BOB, voided, true);
+        assert_eq!(CustomPallet::creator_projected_reward(voided), 0);
+        end_market(voided);
+        let creator_balance = Balances::free_balance(ALICE);
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            voided
+        ));
+        assert_eq!(
+            Balances::free_balance(ALICE),
+            creator_balance + CREATION_DEPOSIT
+        );
+    });
+}
+
+#[test]
+fn creator_projection_is_zero_for_missing_market() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(CustomPallet::creator_projected_reward(42), 0);
+        assert_eq!(CustomPallet::market_participation(42), None);
+    });
+}
+
+#[test]
+fn voting_is_blocked_while_market_is_flagged() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_market(ALICE);
+        assert_ok!(CustomPallet::vote(
+            RuntimeOrigin::signed(BOB),
+            market_id,
+            true
+        ));
+
+        assert_ok!(CustomPallet::flag_market(RuntimeOrigin::root(), market_id));
+        assert!(FlaggedMarkets::<Test>::contains_key(market_id));
+        System::assert_last_event(Event::MarketFlagged { market_id }.into());
+
+        assert_noop!(
+            CustomPallet::vote(RuntimeOrigin::signed(CHARLIE), market_id, true),
+            Error::<Test>::MarketUnderReview
+        );
+        // Existing votes are untouched by the flag.
+        assert!(Votes::<Test>::contains_key(market_id, BOB));
+
+        assert_ok!(CustomPallet::clear_market_flag(
+            RuntimeOrigin::root(),
+            market_id
+        ));
+        System::assert_last_event(Event::MarketFlagCleared { market_id }.into());
+        assert_ok!(CustomPallet::vote(
+            RuntimeOrigin::signed(CHARLIE),
+            market_id,
+            true
+        ));
+    });
+}
+
+#[test]
+fn flagged_market_can_still_resolve() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_market(ALICE);
+        assert_ok!(CustomPallet::vote(
+            RuntimeOrigin::signed(BOB),
+            market_id,
+            true
+        ));
+        assert_ok!(CustomPallet::flag_market(RuntimeOrigin::root(), market_id));
+
+        end_market(market_id);
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            market_id
+        ));
+        assert!(!Markets::<Test>::get(market_id).unwrap().is_active);
+    });
+}
+
+#[test]
+fn only_governance_can_flag_markets() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_market(ALICE);
+
+        assert_noop!(
+            CustomPallet::flag_market(RuntimeOrigin::signed(ALICE), market_id),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            CustomPallet::flag_market(RuntimeOrigin::root(), market_id + 1),
+            Error::<Test>::MarketDoesNotExist
+        );
+    });
+}
+
+#[test]
+fn market_won_by_less_than_margin_is_voided() {
+    new_test_ext().execute_with(|| {
+        MinWinMargin::set(2);
+        let market_id = create_market(ALICE);
+        assert_ok!(CustomPallet::vote(
+            RuntimeOrigin::signed(BOB),
+            market_id,
+            true
+        ));
+        assert_ok!(CustomPallet::vote(
+            RuntimeOrigin::signed(CHARLIE),
+            market_id,
+            true
+        ));
+        assert_ok!(CustomPallet::vote(
+            RuntimeOrigin::signed(DAVE),
+            market_id,
+            false
+        ));
+
+        end_market(market_id);
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            market_id
+        ));
+
+        System::assert_last_event(
+            Event::MarketVoided {
+                market_id,
+                reason: VoidReason::MarginNotMet,
+            }
+            .into(),
+        );
+        assert!(!Markets::<Test>::get(market_id).unwrap().is_active);
+        for who in [ALICE, BOB, CHARLIE, DAVE] {
+            assert_eq!(Balances::free_balance(who), 1_000);
+        }
+    });
+}
+
+#[test]
+fn market_won_by_margin_resolves() {
+    new_test_ext().execute_with(|| {
+        MinWinMargin::set(2);
+        let market_id = create_market(ALICE);
+        assert_ok!(CustomPallet::vote(
+            RuntimeOrigin::signed(BOB),
+            market_id,
+            true
+        ));
+        assert_ok!(CustomPallet::vote(
+            RuntimeOrigin::signed(CHARLIE),
+            market_id,
+            true
+        ));
+
+        end_market(market_id);
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            market_id
+        ));
+
+        System::assert_last_event(
+            Event::RewardsDistributed {
+                market_id,
+                creator: ALICE,
+                creator_reward: 2,
+            }
+            .into(),
+        );
+        assert_eq!(Balances::free_balance(ALICE), 1_002);
+        assert_eq!(Balances::free_balance(BOB), 999);
+    });
+}
+
+#[test]
+fn simulated_yes_stake_shifts_odds_towards_yes() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_market(ALICE);
+        assert_ok!(CustomPallet::vote(
+            RuntimeOrigin::signed(BOB),
+            market_id,
+            true
+        ));
+        assert_ok!(CustomPallet::vote(
+            RuntimeOrigin::signed(CHARLIE),
+            market_id,
+            false
+        ));
+        assert_eq!(
+            CustomPallet::market_odds(market_id),
+            Some((Permill::from_percent(50), Permill::from_percent(50)))
+        );
+
+        // One vote per account, so a larger stake still adds a single yes vote.
+        let (yes, no) = CustomPallet::simulate_vote_impact(market_id, true, 2 * VOTE_COST).unwrap();
+        assert_eq!(yes, Permill::from_rational(2u32, 3u32));
+        assert_eq!(no, Permill::one() - yes);
+
+        // Simulation leaves the market untouched.
+        let market = Markets::<Test>::get(market_id).unwrap();
+        assert_eq!((market.yes_votes, market.no_votes), (1, 1));
+        assert_eq!(market.total_staked, 2 * VOTE_COST);
+    });
+}
+
+#[test]
+fn odds_follow_vote_counts_when_voting_is_free() {
+    new_test_ext().execute_with(|| {
+        OraclePrice::set(Some(0));
+        let market_id = create_market(ALICE);
+        vote(BOB, market_id, true);
+        vote(CHARLIE, market_id, true);
+        vote(DAVE, market_id, false);
+        assert_eq!(Markets::<Test>::get(market_id).unwrap().total_staked, 0);
+
+        let (yes, no) = CustomPallet::market_odds(market_id).unwrap();
+        assert_eq!(yes, Permill::from_rational(2u32, 3u32));
+        assert_eq!(no, Permill::one() - yes);
+        assert_eq!(
+            CustomPallet::simulate_vote_impact(market_id, false, 0),
+            Some((Permill::from_percent(50), Permill::from_percent(50)))
+        );
+    });
+}
+
+#[test]
+fn simulating_on_empty_or_missing_market() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_market(ALICE);
+
+        assert_eq!(
+            CustomPallet::simulate_vote_impact(market_id, false, VOTE_COST),
+            Some((Permill::zero(), Permill::one()))
+        );
+        assert_eq!(
+            CustomPallet::simulate_vote_impact(market_id + 1, true, VOTE_COST),
+            None
+        );
+    });
+}
+
+#[test]
+fn creation_deposit_scales_with_active_markets() {
+    new_test_ext().execute_with(|| {
+        let first = create_market(ALICE);
+        assert_eq!(Balances::reserved_balance(ALICE), CREATION_DEPOSIT);
+        let second = create_market(ALICE);
+        assert_eq!(Balances::reserved_balance(ALICE), 3 * CREATION_DEPOSIT);
+        let third = create_market(ALICE);
+        assert_eq!(Balances::reserved_balance(ALICE), 6 * CREATION_DEPOSIT);
+
+        assert_eq!(
+            Markets::<Test>::get(first).unwrap().deposit,
+            CREATION_DEPOSIT
+        );
+        assert_eq!(
+            Markets::<Test>::get(second).unwrap().deposit,
+            2 * CREATION_DEPOSIT
+        );
+        assert_eq!(
+            Markets::<Test>::get(third).unwrap().deposit,
+            3 * CREATION_DEPOSIT
+        );
+        assert_eq!(CustomPallet::active_market_count(&ALICE), 3);
+        assert_eq!(
+            MarketsByCreator::<Test>::get(ALICE).into_inner(),
+            vec![first, second, third]
+        );
+
+        // Other creators are priced independently.
+        create_market(BOB);
+        assert_eq!(Balances::reserved_balance(BOB), CREATION_DEPOSIT);
+    });
+}
+
+#[test]
+fn creation_deposit_resets_as_markets_resolve() {
+    new_test_ext().execute_with(|| {
+        let first = create_market(ALICE);
+        let second = create_market(ALICE);
+
+        end_market(second);
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            first
+        ));
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            second
+        ));
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert_eq!(CustomPallet::active_market_count(&ALICE), 0);
+
+        let third = create_market(ALICE);
+        assert_eq!(
+            Markets::<Test>::get(third).unwrap().deposit,
+            CREATION_DEPOSIT
+        );
+    });
+}
+
+#[test]
+fn creator_market_index_is_bounded() {
+    new_test_ext().execute_with(|| {
+        for _ in 0..MAX_MARKETS_PER_CREATOR {
+            create_market(ALICE);
+        }
+
+        assert_noop!(
+            CustomPallet::create_market(RuntimeOrigin::signed(ALICE), b"one too many".to_vec()),
+            Error::<Test>::TooManyMarkets
+        );
+    });
+}
+
+#[test]
+fn resolving_markets_frees_creator_slots() {
+    new_test_ext().execute_with(|| {
+        let mut market_ids = Vec::new();
+        for _ in 0..MAX_MARKETS_PER_CREATOR {
+            market_ids.push(create_market(ALICE));
+        }
+        end_market(market_ids[0]);
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            market_ids[0]
+        ));
+        assert!(!MarketsByCreator::<Test>::get(ALICE).contains(&market_ids[0]));
+
+        // The creator is back under the cap without any manual index maintenance.
+        let next = create_market(ALICE);
+        assert!(MarketsByCreator::<Test>::get(ALICE).contains(&next));
+        assert_noop!(
+            CustomPallet::create_market(RuntimeOrigin::signed(ALICE), b"full".to_vec()),
+            Error::<Test>::TooManyMarkets
+        );
+    });
+}
+
+#[test]
+fn resolved_outcome_records_winning_side() {
+    new_test_ext().execute_with(|| {
+        let yes_market = create_market(ALICE);
+        let no_market = create_market(ALICE);
+        vote(BOB, yes_market, true);
+        vote(CHARLIE, no_market, false);
+        assert_eq!(CustomPallet::market_outcome(yes_market), None);
+
+        end_market(no_market);
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            yes_market
+        ));
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            no_market
+        ));
+
+        assert_eq!(CustomPallet::market_outcome(yes_market), Some(Outcome::Yes));
+        assert_eq!(CustomPallet::market_outcome(no_market), Some(Outcome::No));
+    });
+}
+
+#[test]
+fn tied_market_records_tie_and_pays_the_no_side() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_market(ALICE);
+        vote(BOB, market_id, true);
+        vote(CHARLIE, market_id, false);
+
+        end_market(market_id);
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            market_id
+        ));
+
+        assert_eq!(CustomPallet::market_outcome(market_id), Some(Outcome::Tie));
+        assert_eq!(Balances::free_balance(ALICE), 1_002);
+        assert_eq!(Balances::free_balance(BOB), 1_000 - VOTE_COST);
+        assert_eq!(Balances::free_balance(CHARLIE), 1_008);
+    });
+}
+
+#[test]
+fn voided_market_records_void_even_with_a_majority() {
+    new_test_ext().execute_with(|| {
+        MinWinMargin::set(3);
+        let market_id = create_market(ALICE);
+        vote(BOB, market_id, true);
+        vote(CHARLIE, market_id, true);
+
+        end_market(market_id);
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            market_id
+        ));
+
+        let market = Markets::<Test>::get(market_id).unwrap();
+        assert!(market.yes_votes > market.no_votes);
+        assert_eq!(market.resolved_outcome, Some(Outcome::Void));
+    });
+}
+
+#[test]
+fn creator_can_append_metadata_before_first_vote() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_market(ALICE);
+
+        assert_ok!(CustomPallet::append_metadata(
+            RuntimeOrigin::signed(ALICE),
+            market_id,
+            b" in London".to_vec()
+        ));
+
+        let metadata = Markets::<Test>::get(market_id).unwrap().metadata;
+        assert_eq!(metadata.to_vec(), b"will it rain? in London".to_vec());
+        System::assert_last_event(
+            Event::MetadataAppended {
+                market_id,
+                metadata,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn appending_past_metadata_bound_fails() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_market(ALICE);
+        let len = Markets::<Test>::get(market_id).unwrap().metadata.len();
+
+        assert_ok!(CustomPallet::append_metadata(
+            RuntimeOrigin::signed(ALICE),
+            market_id,
+            vec![0u8; 256 - len]
+        ));
+        assert_noop!(
+            CustomPallet::append_metadata(RuntimeOrigin::signed(ALICE), market_id, vec![0u8]),
+            Error::<Test>::MetadataTooLong
+        );
+    });
+}
+
+#[test]
+fn metadata_is_locked_after_first_vote() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_market(ALICE);
+
+        assert_noop!(
+            CustomPallet::append_metadata(RuntimeOrigin::signed(BOB), market_id, b"!".to_vec()),
+            Error::<Test>::NotMarketCreator
+        );
+
+        vote(BOB, market_id, true);
+        assert_noop!(
+            CustomPallet::append_metadata(RuntimeOrigin::signed(ALICE), market_id, b"!".to_vec()),
+            Error::<Test>::MetadataLocked
+        );
+    });
+}
+
+#[test]
+fn lone_winner_market_is_voided_below_min_winners() {
+    new_test_ext().execute_with(|| {
+        MinWinnersToPay::set(2);
+        let market_id = create_market(ALICE);
+        vote(BOB, market_id, true);
+
+        end_market(market_id);
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            market_id
+        ));
+
+        System::assert_last_event(
+            Event::MarketVoided {
+                market_id,
+                reason: VoidReason::TooFewWinners,
+            }
+            .into(),
+        );
+        assert_eq!(CustomPallet::market_outcome(market_id), Some(Outcome::Void));
+        assert_eq!(Balances::free_balance(BOB), 1_000);
+        assert_eq!(Balances::free_balance(ALICE), 1_000);
+    });
+}
+
+#[test]
+fn market_with_enough_winners_pays_out() {
+    new_test_ext().execute_with(|| {
+        MinWinnersToPay::set(2);
+        let market_id = create_market(ALICE);
+        vote(BOB, market_id, true);
+        vote(CHARLIE, market_id, true);
+        vote(DAVE, market_id, false);
+
+        end_market(market_id);
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            market_id
+        ));
+
+        assert_eq!(CustomPallet::market_outcome(market_id), Some(Outcome::Yes));
+        assert!(Balances::free_balance(BOB) > 1_000);
+    });
+}
+
+#[test]
+fn winnings_route_to_payout_beneficiary() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(CustomPallet::set_payout_beneficiary(
+            RuntimeOrigin::signed(BOB),
+            DAVE
+        ));
+        System::assert_last_event(
+            Event::PayoutBeneficiarySet {
+                who: BOB,
+                beneficiary: DAVE,
+            }
+            .into(),
+        );
+
+        let market_id = create_market(ALICE);
+        vote(BOB, market_id, true);
+
+        end_market(market_id);
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            market_id
+        ));
+
+        // Pool of 10, 1 to the creator, 9 to the lone winner's beneficiary.
+        assert_eq!(Balances::free_balance(BOB), 1_000 - VOTE_COST);
+        assert_eq!(Balances::free_balance(DAVE), 1_009);
+    });
+}
+
+#[test]
+fn setting_self_as_beneficiary_clears_it() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(CustomPallet::set_payout_beneficiary(
+            RuntimeOrigin::signed(BOB),
+            DAVE
+        ));
+        assert_eq!(CustomPallet::payout_account(&BOB), DAVE);
+
+        assert_ok!(CustomPallet::set_payout_beneficiary(
+            RuntimeOrigin::signed(BOB),
+            BOB
+        ));
+        assert!(!PayoutBeneficiaries::<Test>::contains_key(BOB));
+        assert_eq!(CustomPallet::payout_account(&BOB), BOB);
+    });
+}
+
+fn resolved_creator_reward(market_id: u32) -> u64 {
+    end_market(market_id);
+    let before = Balances::free_balance(ALICE);
+    let deposit = Markets::<Test>::get(market_id).unwrap().deposit;
+    assert_ok!(CustomPallet::release_rewards(
+        RuntimeOrigin::signed(BOB),
+        market_id
+    ));
+    Balances::free_balance(ALICE) - before - deposit
+}
+
+#[test]
+fn flat_curve_pays_same_percentage_for_any_pool() {
+    new_test_ext().execute_with(|| {
+        let small = create_market(ALICE);
+        vote(BOB, small, true);
+        vote(CHARLIE, small, true);
+        let large = create_market(ALICE);
+        vote(BOB, large, true);
+        vote(CHARLIE, large, true);
+        vote(DAVE, large, false);
+
+        assert_eq!(resolved_creator_reward(small), 2);
+        assert_eq!(resolved_creator_reward(large), 3);
+    });
+}
+
+#[test]
+fn regressive_curve_lowers_creator_percentage_for_large_pools() {
+    new_test_ext().execute_with(|| {
+        RegressiveCreatorReward::set(true);
+        let small = create_market(ALICE);
+        vote(BOB, small, true);
+        vote(CHARLIE, small, true);
+        let large = create_market(ALICE);
+        vote(BOB, large, true);
+        vote(CHARLIE, large, true);
+        vote(DAVE, large, false);
+
+        assert_eq!(CustomPallet::creator_projected_reward(large), 1);
+        assert_eq!(resolved_creator_reward(small), 2);
+        // 5% of 30 rounds down to 1, against 3 under the flat 10%.
+        assert_eq!(resolved_creator_reward(large), 1);
+    });
+}
+
+#[test]
+fn validate_create_market_matches_extrinsic_errors() {
+    new_test_ext().execute_with(|| {
+        let oversized = vec![0u8; 257];
+        assert_eq!(
+            CustomPallet::validate_create_market(&ALICE, oversized.clone()),
+            Err(Error::<Test>::MetadataTooLong.into())
+        );
+        assert_noop!(
+            CustomPallet::create_market(RuntimeOrigin::signed(ALICE), oversized),
+            Error::<Test>::MetadataTooLong
+        );
+
+        // An account with no funds cannot cover the creation deposit.
+        let pauper = 7;
+        assert_eq!(
+            CustomPallet::validate_create_market(&pauper, b"?".to_vec()),
+            Err(Error::<Test>::InsufficientDeposit.into())
+        );
+        assert_noop!(
+            CustomPallet::create_market(RuntimeOrigin::signed(pauper), b"?".to_vec()),
+            Error::<Test>::InsufficientDeposit
+        );
+
+        for _ in 0..MAX_MARKETS_PER_CREATOR {
+            create_market(ALICE);
+        }
+        assert_eq!(
+            CustomPallet::validate_create_market(&ALICE, b"?".to_vec()),
+            Err(Error::<Test>::TooManyMarkets.into())
+        );
+        assert_noop!(
+            CustomPallet::create_market(RuntimeOrigin::signed(ALICE), b"?".to_vec()),
+            Error::<Test>::TooManyMarkets
+        );
+    });
+}
+
+#[test]
+fn validate_create_market_does_not_write() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(CustomPallet::validate_create_market(&ALICE, b"?".to_vec()));
+
+        assert_eq!(crate::MarketCount::<Test>::get(), 0);
+        assert!(MarketsByCreator::<Test>::get(ALICE).is_empty());
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+    });
+}
+
+fn void_market_with_deposit() -> u32 {
+    MinWinnersToPay::set(2);
+    let market_id = create_market(ALICE);
+    vote(BOB, market_id, true);
+    end_market(market_id);
+    assert_ok!(CustomPallet::release_rewards(
+        RuntimeOrigin::signed(BOB),
+        market_id
+    ));
+    assert_eq!(CustomPallet::market_outcome(market_id), Some(Outcome::Void));
+    market_id
+}
+
+#[test]
+fn deposit_is_returned_on_void() {
+    new_test_ext().execute_with(|| {
+        DepositOnVoid::set(DepositPolicy::Return);
+        void_market_with_deposit();
+
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert_eq!(Balances::free_balance(ALICE), 1_000);
+        assert_eq!(Balances::free_balance(TREASURY), 0);
+    });
+}
+
+#[test]
+fn deposit_is_slashed_to_treasury_on_void() {
+    new_test_ext().execute_with(|| {
+        DepositOnVoid::set(DepositPolicy::Slash);
+        let issuance = Balances::total_issuance();
+        void_market_with_deposit();
+
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert_eq!(Balances::free_balance(ALICE), 1_000 - CREATION_DEPOSIT);
+        assert_eq!(Balances::free_balance(TREASURY), CREATION_DEPOSIT);
+        assert_eq!(Balances::total_issuance(), issuance);
+    });
+}
+
+#[test]
+fn deposit_is_burned_on_void() {
+    new_test_ext().execute_with(|| {
+        DepositOnVoid::set(DepositPolicy::Burn);
+        let issuance = Balances::total_issuance();
+        void_market_with_deposit();
+
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert_eq!(Balances::free_balance(ALICE), 1_000 - CREATION_DEPOSIT);
+        assert_eq!(Balances::free_balance(TREASURY), 0);
+        assert_eq!(Balances::total_issuance(), issuance - CREATION_DEPOSIT);
+    });
+}
+
+#[test]
+fn deposit_is_returned_after_normal_resolution_regardless_of_policy() {
+    new_test_ext().execute_with(|| {
+        DepositOnVoid::set(DepositPolicy::Burn);
+        let market_id = create_market(ALICE);
+        vote(BOB, market_id, true);
+        end_market(market_id);
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            market_id
+        ));
+
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert_eq!(Balances::free_balance(ALICE), 1_000 + 1);
+    });
+}
+
+#[test]
+fn market_closes_when_vote_cap_is_hit() {
+    new_test_ext().execute_with(|| {
+        MaxVotesPerMarket::set(2);
+        let market_id = create_market(ALICE);
+
+        vote(BOB, market_id, true);
+        assert!(!Markets::<Test>::get(market_id).unwrap().voting_closed);
+
+        vote(CHARLIE, market_id, false);
+        assert!(Markets::<Test>::get(market_id).unwrap().voting_closed);
+        System::assert_last_event(Event::VotingClosed { market_id }.into());
+
+        assert_noop!(
+            CustomPallet::vote(RuntimeOrigin::signed(DAVE), market_id, true),
+            Error::<Test>::VotingClosed
+        );
+    });
+}
+
+#[test]
+fn closed_market_can_resolve_before_end_block() {
+    new_test_ext().execute_with(|| {
+        MaxVotesPerMarket::set(1);
+        let market_id = create_market(ALICE);
+        assert_noop!(
+            CustomPallet::release_rewards(RuntimeOrigin::signed(BOB), market_id),
+            Error::<Test>::MarketStillActive
+        );
+
+        vote(BOB, market_id, true);
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            market_id
+        ));
+        assert_eq!(CustomPallet::market_outcome(market_id), Some(Outcome::Yes));
+    });
+}
+
+#[test]
+fn all_config_constants_are_exposed_in_metadata() {
+    use frame_support::PalletId;
+    use scale_info::{meta_type, MetaType};
+
+    let constants: Vec<(&str, MetaType)> = CustomPallet::pallet_constants_metadata()
+        .into_iter()
+        .map(|constant| (constant.name, constant.ty))
+        .collect();
+
+    assert_eq!(
+        constants,
+        vec![
+            ("PalletId", meta_type::<PalletId>()),
+            ("VoteCost", meta_type::<u64>()),
+            ("CreatorRewardPercentage", meta_type::<u32>()),
+            ("MarketDuration", meta_type::<u32>()),
+            ("StakeFreezeWindow", meta_type::<u32>()),
+            ("LateStakeCap", meta_type::<u64>()),
+            ("MinWinMargin", meta_type::<u32>()),
+            ("CreationDeposit", meta_type::<u64>()),
+            ("MaxMarketsPerCreator", meta_type::<u32>()),
+            ("MinWinnersToPay", meta_type::<u32>()),
+            ("DepositOnVoid", meta_type::<DepositPolicy>()),
+            ("TreasuryAccount", meta_type::<u64>()),
+            ("MaxVotesPerMarket", meta_type::<u32>()),
+            ("FreeVoteQuota", meta_type::<u32>()),
+            ("BurnPercentage", meta_type::<Permill>()),
+            ("AllowRetroactiveEndBlock", meta_type::<bool>()),
+            ("HousePercentage", meta_type::<Permill>()),
+            ("EscalationDelay", meta_type::<u32>()),
+            ("MaxEscalationChecksPerBlock", meta_type::<u32>()),
+            ("MaxEscalatedMarkets", meta_type::<u32>()),
+            ("SnapshotOffset", meta_type::<u32>()),
+            ("EmitBlockSummary", meta_type::<bool>()),
+            ("VoteCooldown", meta_type::<u32>()),
+            ("AllowCreatorVoting", meta_type::<bool>()),
+            ("KeeperBond", meta_type::<u64>()),
+            ("KeeperTip", meta_type::<u64>()),
+            ("KeeperUnbondingPeriod", meta_type::<u32>()),
+            ("MaxRewardPerAccount", meta_type::<u64>()),
+            ("CloseWhenDecided", meta_type::<bool>()),
+            ("LoyaltyThreshold", meta_type::<u32>()),
+            ("LoyaltyBonus", meta_type::<u64>()),
+        ]
+    );
+}
+
+#[test]
+fn exported_outcome_attestation_round_trips_and_verifies() {
+    use codec::{Decode, Encode};
+
+    new_test_ext().execute_with(|| {
+        let market_id = create_market(ALICE);
+        vote(BOB, market_id, true);
+        vote(CHARLIE, market_id, true);
+        vote(DAVE, market_id, false);
+        assert_eq!(CustomPallet::export_outcome(market_id), None);
+
+        end_market(market_id);
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            market_id
+        ));
+
+        let attestation = CustomPallet::export_outcome(market_id).unwrap();
+        assert_eq!(attestation.outcome, Outcome::Yes);
+        assert_eq!((attestation.yes_votes, attestation.no_votes), (2, 1));
+        assert_eq!(attestation.genesis_hash, System::block_hash(0));
+        assert_eq!(attestation.resolved_at, System::block_number());
+        assert_eq!(
+            Markets::<Test>::get(market_id).unwrap().resolved_at,
+            Some(System::block_number())
+        );
+
+        let decoded = crate::OutcomeAttestation::decode(&mut &attestation.encode()[..]).unwrap();
+        assert!(CustomPallet::verify_outcome_attestation(&decoded));
+    });
+}
+
+#[test]
+fn tampered_outcome_attestation_fails_verification() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_market(ALICE);
+        vote(BOB, market_id, true);
+        end_market(market_id);
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            market_id
+        ));
+        let attestation = CustomPallet::export_outcome(market_id).unwrap();
+
+        let mut flipped = attestation.clone();
+        flipped.outcome = Outcome::No;
+        assert!(!CustomPallet::verify_outcome_attestation(&flipped));
+
+        let mut inflated = attestation.clone();
+        inflated.total_staked += 1;
+        assert!(!CustomPallet::verify_outcome_attestation(&inflated));
+
+        let mut other_market = attestation.clone();
+        other_market.market_id += 1;
+        assert!(!CustomPallet::verify_outcome_attestation(&other_market));
+
+        let mut other_chain = attestation.clone();
+        other_chain.genesis_hash = sp_core::H256::repeat_byte(0xaa);
+        assert!(!CustomPallet::verify_outcome_attestation(&other_chain));
+
+        let mut backdated = attestation;
+        backdated.resolved_at -= 1;
+        assert!(!CustomPallet::verify_outcome_attestation(&backdated));
+    });
+}
+
+fn vote_fee(voter: u64, market_id: u32) -> Pays {
+    CustomPallet::vote(RuntimeOrigin::signed(voter), market_id, true)
+        .unwrap()
+        .pays_fee
+}
+
+#[test]
+fn first_vote_in_new_market_is_fee_free() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_market(ALICE);
+
+        assert_eq!(vote_fee(BOB, market_id), Pays::No);
+        assert_eq!(vote_fee(CHARLIE, market_id), Pays::Yes);
+        assert_eq!(vote_fee(DAVE, market_id), Pays::Yes);
+        assert_eq!(FreeVotesUsed::<Test>::get(BOB), 1);
+        assert_eq!(FreeVotesUsed::<Test>::get(CHARLIE), 0);
+    });
+}
+
+#[test]
+fn free_first_votes_are_bounded_per_account() {
+    new_test_ext().execute_with(|| {
+        for _ in 0..FREE_VOTE_QUOTA {
+            let market_id = create_market(ALICE);
+            assert_eq!(vote_fee(BOB, market_id), Pays::No);
+        }
+
+        let market_id = create_market(ALICE);
+        assert_eq!(vote_fee(BOB, market_id), Pays::Yes);
+        assert_eq!(FreeVotesUsed::<Test>::get(BOB), FREE_VOTE_QUOTA);
+    });
+}
+
+#[test]
+fn resolution_waits_for_reference_time() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_market(ALICE);
+        assert_ok!(CustomPallet::set_resolve_after(
+            RuntimeOrigin::signed(ALICE),
+            market_id,
+            1_000
+        ));
+        System::assert_last_event(
+            Event::ResolveAfterSet {
+                market_id,
+                resolve_after: 1_000,
+            }
+            .into(),
+        );
+        vote(BOB, market_id, true);
+
+        end_market(market_id);
+        Now::set(999);
+        assert_noop!(
+            CustomPallet::release_rewards(RuntimeOrigin::signed(BOB), market_id),
+            Error::<Test>::ReferenceTimeNotReached
+        );
+
+        Now::set(1_000);
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            market_id
+        ));
+    });
+}
+
+#[test]
+fn reference_time_does_not_bypass_end_block() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_market(ALICE);
+        assert_ok!(CustomPallet::set_resolve_after(
+            RuntimeOrigin::signed(ALICE),
+            market_id,
+            1_000
+        ));
+
+        Now::set(2_000);
+        assert_noop!(
+            CustomPallet::release_rewards(RuntimeOrigin::signed(BOB), market_id),
+            Error::<Test>::MarketStillActive
+        );
+    });
+}
+
+#[test]
+fn resolve_after_is_locked_after_first_vote() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_market(ALICE);
+        assert_noop!(
+            CustomPallet::set_resolve_after(RuntimeOrigin::signed(BOB), market_id, 1_000),
+            Error::<Test>::NotMarketCreator
+        );
+
+        vote(BOB, market_id, true);
+        assert_noop!(
+            CustomPallet::set_resolve_after(RuntimeOrigin::signed(ALICE), market_id, 1_000),
+            Error::<Test>::ResolveAfterLocked
+        );
+    });
+}
+
+#[test]
+fn vote_power_sums_to_the_tallies_used_at_resolution() {
+    new_test_ext().execute_with(|| {
+        SnapshotOffset::set(3);
+        let market_id = create_market(ALICE);
+        vote(BOB, market_id, true);
+        vote(CHARLIE, market_id, true);
+        let end_block = Markets::<Test>::get(market_id).unwrap().end_block;
+        run_to_block(end_block - 2);
+        vote(DAVE, market_id, false);
+
+        let side_power = |side: bool| -> u128 {
+            Votes::<Test>::iter_prefix(market_id)
+                .filter(|(_, vote)| *vote == side)
+                .map(|(voter, _)| CustomPallet::effective_vote_power(market_id, &voter))
+                .sum()
+        };
+        // Resolution reads the snapshot, which leaves out DAVE's late vote.
+        let (yes_votes, no_votes) = Markets::<Test>::get(market_id).unwrap().snapshot.unwrap();
+        assert_eq!(side_power(true), yes_votes as u128);
+        assert_eq!(side_power(false), no_votes as u128);
+
+        assert_eq!(CustomPallet::effective_vote_power(market_id, &BOB), 1);
+        assert_eq!(CustomPallet::effective_vote_power(market_id, &DAVE), 0);
+        assert_eq!(CustomPallet::effective_vote_power(market_id, &ALICE), 0);
+    });
+}
+
+#[test]
+fn burn_percentage_reduces_issuance_before_payouts() {
+    new_test_ext().execute_with(|| {
+        BurnPercentage::set(Permill::from_percent(10));
+        let market_id = create_market(ALICE);
+        vote(BOB, market_id, true);
+        vote(CHARLIE, market_id, true);
+        vote(DAVE, market_id, false);
+        end_market(market_id);
+
+        // 3 of the 30 pool is burned; the creator takes 10% of the remaining 27.
+        assert_eq!(CustomPallet::creator_projected_reward(market_id), 2);
+        let issuance = Balances::total_issuance();
+        let creator_before = Balances::free_balance(ALICE);
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            market_id
+        ));
+
+        System::assert_has_event(
+            Event::PoolBurned {
+                market_id,
+                amount: 3,
+            }
+            .into(),
+        );
+        assert_eq!(Balances::total_issuance(), issuance - 3);
+        assert_eq!(
+            Balances::free_balance(ALICE),
+            creator_before + CREATION_DEPOSIT + 2
+        );
+        assert_eq!(Balances::free_balance(BOB), 1_000 - VOTE_COST + 12);
+        assert_eq!(Balances::free_balance(CHARLIE), 1_000 - VOTE_COST + 12);
+    });
+}
+
+#[test]
+fn governance_can_shorten_and_lengthen_a_voted_market() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_market(ALICE);
+        vote(BOB, market_id, true);
+        let end_block = Markets::<Test>::get(market_id).unwrap().end_block;
+
+        assert_noop!(
+            CustomPallet::governance_set_end_block(RuntimeOrigin::signed(ALICE), market_id, 5),
+            DispatchError::BadOrigin
+        );
+
+        assert_ok!(CustomPallet::governance_set_end_block(
+            RuntimeOrigin::root(),
+            market_id,
+            5
+        ));
+        System::assert_last_event(
+            Event::EndBlockSet {
+                market_id,
+                end_block: 5,
+            }
+            .into(),
+        );
+
+        assert_ok!(CustomPallet::governance_set_end_block(
+            RuntimeOrigin::root(),
+            market_id,
+            end_block + 20
+        ));
+        assert_eq!(
+            Markets::<Test>::get(market_id).unwrap().end_block,
+            end_block + 20
+        );
+    });
+}
+
+#[test]
+fn extending_a_market_past_its_snapshot_starts_the_snapshot_over() {
+    new_test_ext().execute_with(|| {
+        SnapshotOffset::set(3);
+        let market_id = create_market(ALICE);
+        vote(BOB, market_id, true);
+        let end_block = Markets::<Test>::get(market_id).unwrap().end_block;
+        run_to_block(end_block - 2);
+        vote(CHARLIE, market_id, false);
+        vote(DAVE, market_id, false);
+        assert_eq!(
+            Markets::<Test>::get(market_id).unwrap().snapshot,
+            Some((1, 0))
+        );
+
+        assert_ok!(CustomPallet::governance_set_end_block(
+            RuntimeOrigin::root(),
+            market_id,
+            end_block + 10
+        ));
+        assert_eq!(Markets::<Test>::get(market_id).unwrap().snapshot, None);
+        assert!(!crate::LateVotes::<Test>::contains_key(market_id, CHARLIE));
+        assert_eq!(CustomPallet::effective_vote_power(market_id, &CHARLIE), 1);
+
+        // No one votes past the new snapshot block, so the closing tallies decide.
+        end_market(market_id);
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            market_id
+        ));
+        assert_eq!(CustomPallet::market_outcome(market_id), Some(Outcome::No));
+    });
+}
+
+#[test]
+fn governance_cannot_end_a_voted_market_in_the_past_unless_allowed() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_market(ALICE);
+        let fresh_id = create_market(BOB);
+        vote(BOB, market_id, true);
+        run_to_block(5);
+
+        assert_noop!(
+            CustomPallet::governance_set_end_block(RuntimeOrigin::root(), market_id, 4),
+            Error::<Test>::EndBlockInPast
+        );
+        // A market without votes has nothing to strand.
+        assert_ok!(CustomPallet::governance_set_end_block(
+            RuntimeOrigin::root(),
+            fresh_id,
+            4
+        ));
+
+        AllowRetroactiveEndBlock::set(true);
+        assert_ok!(CustomPallet::governance_set_end_block(
+            RuntimeOrigin::root(),
+            market_id,
+            4
+        ));
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            market_id
+        ));
+
+        assert_noop!(
+            CustomPallet::governance_set_end_block(RuntimeOrigin::root(), market_id, 20),
+            Error::<Test>::MarketNotActive
+        );
+    });
+}
+
+#[test]
+fn proxied_vote_is_credited_to_the_principal() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_market(ALICE);
+        assert_ok!(Proxy::add_proxy(
+            RuntimeOrigin::signed(CHARLIE),
+            DAVE,
+            (),
+            0
+        ));
+
+        let call = RuntimeCall::CustomPallet(crate::Call::vote {
+            market_id,
+            vote_yes: true,
+        });
+        assert_ok!(Proxy::proxy(
+            RuntimeOrigin::signed(DAVE),
+            CHARLIE,
+            None,
+            Box::new(call)
+        ));
+
+        assert!(Votes::<Test>::get(market_id, CHARLIE));
+        assert!(!Votes::<Test>::contains_key(market_id, DAVE));
+        assert_eq!(Balances::free_balance(DAVE), 1_000);
+
+        end_market(market_id);
+        let principal_before = Balances::free_balance(CHARLIE);
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            market_id
+        ));
+        assert_eq!(
+            Balances::free_balance(CHARLIE),
+            principal_before + VOTE_COST - 1
+        );
+        assert_eq!(Balances::free_balance(DAVE), 1_000);
+    });
+}
+
+#[test]
+fn drained_pool_halts_resolution_without_partial_payouts() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_market(ALICE);
+        vote(BOB, market_id, true);
+        vote(CHARLIE, market_id, true);
+        end_market(market_id);
+
+        let pool = CustomPallet::account_id();
+        Balances::make_free_balance_be(&pool, VOTE_COST);
+        assert_noop!(
+            CustomPallet::release_rewards(RuntimeOrigin::signed(BOB), market_id),
+            Error::<Test>::InsufficientPoolBalance
+        );
+        assert!(Markets::<Test>::get(market_id).unwrap().is_active);
+
+        // Once the pool is topped back up the same market settles normally.
+        Balances::make_free_balance_be(&pool, 2 * VOTE_COST);
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            market_id
+        ));
+        assert_eq!(Balances::free_balance(BOB), 1_000 - VOTE_COST + 9);
+        assert_eq!(Balances::free_balance(CHARLIE), 1_000 - VOTE_COST + 9);
+    });
+}
+
+#[test]
+fn house_edge_is_skimmed_from_the_winners_pool() {
+    new_test_ext().execute_with(|| {
+        HousePercentage::set(Permill::from_percent(50));
+        let market_id = create_market(ALICE);
+        vote(BOB, market_id, true);
+        vote(CHARLIE, market_id, true);
+        end_market(market_id);
+
+        // The creator takes 2 of the 20 pool; half of the remaining 18 goes to the house.
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            market_id
+        ));
+        System::assert_has_event(
+            Event::HouseEdgeCollected {
+                market_id,
+                amount: 9,
+            }
+            .into(),
+        );
+        assert_eq!(Balances::free_balance(TREASURY), 9);
+        assert_eq!(Balances::free_balance(BOB), 1_000 - VOTE_COST + 4);
+        assert_eq!(Balances::free_balance(CHARLIE), 1_000 - VOTE_COST + 4);
+    });
+}
+
+#[test]
+fn account_pnl_tracks_wins_losses_and_refunds() {
+    new_test_ext().execute_with(|| {
+        let won = create_market(ALICE);
+        let lost = create_market(ALICE);
+        let tied = create_market(ALICE);
+        vote(BOB, won, true);
+        vote(CHARLIE, won, true);
+        vote(DAVE, won, false);
+        vote(BOB, lost, false);
+        vote(CHARLIE, lost, true);
+        vote(DAVE, lost, true);
+        vote(BOB, tied, true);
+        vote(CHARLIE, tied, false);
+        assert_eq!(CustomPallet::account_pnl(&BOB), (3 * VOTE_COST, 0));
+
+        for market_id in [won, lost, tied] {
+            end_market(market_id);
+            assert_ok!(CustomPallet::release_rewards(
+                RuntimeOrigin::signed(BOB),
+                market_id
+            ));
+        }
+
+        // 30-stake pools pay 27 split between two winners; the tie pays its No voter 18.
+        assert_eq!(CustomPallet::account_pnl(&BOB), (3 * VOTE_COST, 13));
+        assert_eq!(
+            CustomPallet::account_pnl(&CHARLIE),
+            (3 * VOTE_COST, 13 + 13 + 18)
+        );
+        assert_eq!(CustomPallet::account_pnl(&DAVE), (2 * VOTE_COST, 13));
+        assert_eq!(CustomPallet::account_pnl(&ALICE), (0, 0));
+    });
+}
+
+fn initialize_block(n: u64) {
+    run_to_block(n);
+    CustomPallet::on_initialize(n);
+}
+
+#[test]
+fn unresolved_market_escalates_after_the_delay() {
+    new_test_ext().execute_with(|| {
+        let stuck = create_market(ALICE);
+        let resolved = create_market(ALICE);
+        vote(BOB, stuck, true);
+        vote(BOB, resolved, true);
+        let end_block = Markets::<Test>::get(stuck).unwrap().end_block;
+
+        end_market(resolved);
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            resolved
+        ));
+
+        initialize_block(end_block + ESCALATION_DELAY as u64);
+        assert!(GovernanceResolutionQueue::<Test>::get().is_empty());
+
+        initialize_block(end_block + ESCALATION_DELAY as u64 + 1);
+        assert_eq!(
+            GovernanceResolutionQueue::<Test>::get().into_inner(),
+            vec![stuck]
+        );
+        System::assert_last_event(Event::MarketEscalated { market_id: stuck }.into());
+
+        // Later scans don't queue it twice, and resolving it clears the entry.
+        initialize_block(end_block + ESCALATION_DELAY as u64 + 2);
+        assert_eq!(GovernanceResolutionQueue::<Test>::get().len(), 1);
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            stuck
+        ));
+        assert!(GovernanceResolutionQueue::<Test>::get().is_empty());
+    });
+}
+
+#[test]
+fn ineligible_winners_shares_go_to_eligible_winners() {
+    new_test_ext().execute_with(|| {
+        IneligibleWinners::set(vec![CHARLIE]);
+        let market_id = create_market(ALICE);
+        vote(BOB, market_id, true);
+        vote(CHARLIE, market_id, true);
+        vote(DAVE, market_id, false);
+        end_market(market_id);
+
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            market_id
+        ));
+        assert_eq!(Balances::free_balance(BOB), 1_000 - VOTE_COST + 27);
+        assert_eq!(Balances::free_balance(CHARLIE), 1_000 - VOTE_COST);
+        // Each voter on the winning side is checked exactly once.
+        assert_eq!(EligibilityChecks::get(), 2);
+    });
+}
+
+#[test]
+fn market_without_eligible_winners_is_voided() {
+    new_test_ext().execute_with(|| {
+        IneligibleWinners::set(vec![BOB, CHARLIE]);
+        let market_id = create_market(ALICE);
+        vote(BOB, market_id, true);
+        vote(CHARLIE, market_id, true);
+        vote(DAVE, market_id, false);
+        end_market(market_id);
+
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            market_id
+        ));
+        System::assert_last_event(
+            Event::MarketVoided {
+                market_id,
+                reason: VoidReason::NoEligibleWinners,
+            }
+            .into(),
+        );
+        for voter in [BOB, CHARLIE, DAVE] {
+            assert_eq!(Balances::free_balance(voter), 1_000);
+        }
+    });
+}
+
+#[test]
+fn votes_after_the_snapshot_do_not_change_the_outcome() {
+    new_test_ext().execute_with(|| {
+        SnapshotOffset::set(3);
+        let market_id = create_market(ALICE);
+        vote(BOB, market_id, true);
+        let end_block = Markets::<Test>::get(market_id).unwrap().end_block;
+
+        run_to_block(end_block - 2);
+        vote(CHARLIE, market_id, false);
+        vote(DAVE, market_id, false);
+        let market = Markets::<Test>::get(market_id).unwrap();
+        assert_eq!(market.snapshot, Some((1, 0)));
+        assert_eq!((market.yes_votes, market.no_votes), (1, 2));
+
+        end_market(market_id);
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            market_id
+        ));
+
+        // Late stakes still fund the pool: BOB takes it all, less the creator's 3.
+        assert_eq!(CustomPallet::market_outcome(market_id), Some(Outcome::Yes));
+        assert_eq!(Balances::free_balance(BOB), 1_000 - VOTE_COST + 27);
+        assert_eq!(Balances::free_balance(CHARLIE), 1_000 - VOTE_COST);
+    });
+}
+
+#[test]
+fn block_summary_reports_each_blocks_activity() {
+    new_test_ext().execute_with(|| {
+        EmitBlockSummary::set(true);
+        let resolved = create_market(ALICE);
+        let voided = create_market(BOB);
+        CustomPallet::on_finalize(1);
+        System::assert_last_event(
+            Event::BlockSummary {
+                created: 2,
+                resolved: 0,
+                cancelled: 0,
+            }
+            .into(),
+        );
+
+        vote(CHARLIE, resolved, true);
+        end_market(resolved);
+        MinWinnersToPay::set(1);
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            resolved
+        ));
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            voided
+        ));
+        CustomPallet::on_finalize(System::block_number());
+        System::assert_last_event(
+            Event::BlockSummary {
+                created: 0,
+                resolved: 1,
+                cancelled: 1,
+            }
+            .into(),
+        );
+
+        // Quiet blocks emit nothing.
+        System::reset_events();
+        CustomPallet::on_finalize(System::block_number());
+        assert!(System::events().is_empty());
+    });
+}
+
+#[test]
+fn vote_cooldown_throttles_votes_across_markets() {
+    new_test_ext().execute_with(|| {
+        VoteCooldown::set(3);
+        let first = create_market(ALICE);
+        let second = create_market(ALICE);
+        vote(BOB, first, true);
+
+        assert_noop!(
+            CustomPallet::vote(RuntimeOrigin::signed(BOB), second, true),
+            Error::<Test>::VoteCooldownActive
+        );
+        run_to_block(3);
+        assert_noop!(
+            CustomPallet::vote(RuntimeOrigin::signed(BOB), second, true),
+            Error::<Test>::VoteCooldownActive
+        );
+        // Other accounts are unaffected.
+        vote(CHARLIE, second, true);
+
+        run_to_block(4);
+        vote(BOB, second, true);
+    });
+}
+
+#[test]
+fn create_and_vote_records_market_and_creator_vote() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(CustomPallet::create_and_vote(
+            RuntimeOrigin::signed(ALICE),
+            b"one shot".to_vec(),
+            false
+        ));
+
+        let market = Markets::<Test>::get(0).unwrap();
+        assert_eq!(market.creator, ALICE);
+        assert_eq!((market.yes_votes, market.no_votes), (0, 1));
+        assert!(Votes::<Test>::contains_key(0, ALICE));
+        assert!(!Votes::<Test>::get(0, ALICE));
+    });
+}
+
+#[test]
+fn create_and_vote_respects_creator_voting_policy() {
+    new_test_ext().execute_with(|| {
+        AllowCreatorVoting::set(false);
+        assert_noop!(
+            CustomPallet::create_and_vote(RuntimeOrigin::signed(ALICE), b"?".to_vec(), true),
+            Error::<Test>::CreatorCannotVote
+        );
+        assert_eq!(crate::MarketCount::<Test>::get(), 0);
+
+        let market_id = create_market(ALICE);
+        assert_noop!(
+            CustomPallet::vote(RuntimeOrigin::signed(ALICE), market_id, true),
+            Error::<Test>::CreatorCannotVote
+        );
+        vote(BOB, market_id, true);
+    });
+}
+
+#[test]
+#[should_panic(expected = "creator reward, house edge and burn percentages exceed 100%")]
+fn integrity_test_rejects_over_allocated_percentages() {
+    // The creator already takes 10%.
+    HousePercentage::set(Permill::from_percent(50));
+    BurnPercentage::set(Permill::from_percent(41));
+    CustomPallet::integrity_test();
+}
+
+#[test]
+fn staked_deposit_returns_with_yield() {
+    new_test_ext().execute_with(|| {
+        DepositYield::set(2);
+        let market_id = create_market(ALICE);
+        assert_eq!(Balances::reserved_balance(ALICE), CREATION_DEPOSIT);
+        vote(BOB, market_id, true);
+        end_market(market_id);
+
+        let before = Balances::free_balance(ALICE);
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            market_id
+        ));
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        // Deposit and yield, plus the 10% creator cut of the 10 pool.
+        assert_eq!(
+            Balances::free_balance(ALICE),
+            before + CREATION_DEPOSIT + 2 + 1
+        );
+        assert_eq!(Balances::free_balance(YIELD_SOURCE), 1_000 - 2);
+    });
+}
+
+#[test]
+fn slashed_deposit_takes_its_yield_to_the_treasury() {
+    new_test_ext().execute_with(|| {
+        DepositYield::set(2);
+        DepositOnVoid::set(DepositPolicy::Slash);
+        void_market_with_deposit();
+
+        assert_eq!(Balances::free_balance(ALICE), 1_000 - CREATION_DEPOSIT);
+        assert_eq!(Balances::free_balance(TREASURY), CREATION_DEPOSIT + 2);
+    });
+}
+
+fn resolvable_market() -> u32 {
+    let market_id = create_market(ALICE);
+    vote(BOB, market_id, true);
+    end_market(market_id);
+    market_id
+}
+
+#[test]
+fn only_registered_keepers_earn_resolution_tips() {
+    new_test_ext().execute_with(|| {
+        Balances::make_free_balance_be(&TREASURY, 100);
+        assert_ok!(CustomPallet::register_keeper(RuntimeOrigin::signed(DAVE)));
+        assert_eq!(Balances::reserved_balance(DAVE), KEEPER_BOND);
+        assert_noop!(
+            CustomPallet::register_keeper(RuntimeOrigin::signed(DAVE)),
+            Error::<Test>::AlreadyKeeper
+        );
+
+        let first = resolvable_market();
+        let second = resolvable_market();
+        let charlie_before = Balances::free_balance(CHARLIE);
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(CHARLIE),
+            first
+        ));
+        assert_eq!(Balances::free_balance(CHARLIE), charlie_before);
+
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(DAVE),
+            second
+        ));
+        System::assert_has_event(
+            Event::KeeperTipPaid {
+                keeper: DAVE,
+                market_id: second,
+                amount: KEEPER_TIP,
+            }
+            .into(),
+        );
+        assert_eq!(
+            Balances::free_balance(DAVE),
+            1_000 - KEEPER_BOND + KEEPER_TIP
+        );
+        assert_eq!(Balances::free_balance(TREASURY), 100 - KEEPER_TIP);
+
+        assert_ok!(CustomPallet::deregister_keeper(RuntimeOrigin::signed(DAVE)));
+        assert_noop!(
+            CustomPallet::deregister_keeper(RuntimeOrigin::signed(DAVE)),
+            Error::<Test>::NotKeeper
+        );
+
+        // A deregistered keeper no longer earns tips.
+        let third = resolvable_market();
+        let dave_before = Balances::free_balance(DAVE);
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(DAVE),
+            third
+        ));
+        assert_eq!(Balances::free_balance(DAVE), dave_before);
+    });
+}
+
+#[test]
+fn keeper_bond_unlocks_after_the_unbonding_period() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            CustomPallet::withdraw_keeper_bond(RuntimeOrigin::signed(DAVE)),
+            Error::<Test>::NotKeeper
+        );
+        assert_ok!(CustomPallet::register_keeper(RuntimeOrigin::signed(DAVE)));
+        assert_noop!(
+            CustomPallet::withdraw_keeper_bond(RuntimeOrigin::signed(DAVE)),
+            Error::<Test>::KeeperBondLocked
+        );
+
+        assert_ok!(CustomPallet::deregister_keeper(RuntimeOrigin::signed(DAVE)));
+        let unbond_at = System::block_number() + KEEPER_UNBONDING_PERIOD as u64;
+        System::assert_last_event(
+            Event::KeeperDeregistered {
+                who: DAVE,
+                unbond_at,
+            }
+            .into(),
+        );
+        assert_eq!(Balances::reserved_balance(DAVE), KEEPER_BOND);
+
+        run_to_block(unbond_at - 1);
+        assert_noop!(
+            CustomPallet::withdraw_keeper_bond(RuntimeOrigin::signed(DAVE)),
+            Error::<Test>::KeeperBondLocked
+        );
+
+        run_to_block(unbond_at);
+        assert_ok!(CustomPallet::withdraw_keeper_bond(RuntimeOrigin::signed(
+            DAVE
+        )));
+        System::assert_last_event(
+            Event::KeeperBondWithdrawn {
+                who: DAVE,
+                amount: KEEPER_BOND,
+            }
+            .into(),
+        );
+        assert_eq!(Balances::reserved_balance(DAVE), 0);
+        assert!(!crate::Keepers::<Test>::contains_key(DAVE));
+    });
+}
+
+#[test]
+fn deregistered_keeper_stays_slashable_until_its_bond_unlocks() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(CustomPallet::register_keeper(RuntimeOrigin::signed(DAVE)));
+        assert_ok!(CustomPallet::deregister_keeper(RuntimeOrigin::signed(DAVE)));
+
+        run_to_block(System::block_number() + KEEPER_UNBONDING_PERIOD as u64 - 1);
+        assert_ok!(CustomPallet::slash_keeper(RuntimeOrigin::root(), DAVE));
+        assert_eq!(Balances::reserved_balance(DAVE), 0);
+        assert_eq!(Balances::free_balance(TREASURY), KEEPER_BOND);
+        assert_noop!(
+            CustomPallet::withdraw_keeper_bond(RuntimeOrigin::signed(DAVE)),
+            Error::<Test>::NotKeeper
+        );
+
+        // Once the bond has unlocked it belongs to the keeper again.
+        assert_ok!(CustomPallet::register_keeper(RuntimeOrigin::signed(BOB)));
+        assert_ok!(CustomPallet::deregister_keeper(RuntimeOrigin::signed(BOB)));
+        run_to_block(System::block_number() + KEEPER_UNBONDING_PERIOD as u64);
+        assert_noop!(
+            CustomPallet::slash_keeper(RuntimeOrigin::root(), BOB),
+            Error::<Test>::NotKeeper
+        );
+    });
+}
+
+#[test]
+fn governance_can_slash_a_keeper_bond() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(CustomPallet::register_keeper(RuntimeOrigin::signed(DAVE)));
+        assert_noop!(
+            CustomPallet::slash_keeper(RuntimeOrigin::signed(ALICE), DAVE),
+            DispatchError::BadOrigin
+        );
+
+        assert_ok!(CustomPallet::slash_keeper(RuntimeOrigin::root(), DAVE));
+        System::assert_last_event(
+            Event::KeeperSlashed {
+                who: DAVE,
+                amount: KEEPER_BOND,
+            }
+            .into(),
+        );
+        assert_eq!(Balances::reserved_balance(DAVE), 0);
+        assert_eq!(Balances::free_balance(DAVE), 1_000 - KEEPER_BOND);
+        assert_eq!(Balances::free_balance(TREASURY), KEEPER_BOND);
+        assert!(!crate::Keepers::<Test>::contains_key(DAVE));
+    });
+}
+
+#[test]
+fn vote_charge_tracks_the_oracle_price() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_market(ALICE);
+        OraclePrice::set(Some(2));
+        vote(BOB, market_id, true);
+        OraclePrice::set(Some(3));
+        vote(CHARLIE, market_id, false);
+        // With the oracle down the last charge is reused.
+        OraclePrice::set(None);
+        vote(DAVE, market_id, false);
+
+        assert_eq!(Balances::free_balance(BOB), 1_000 - 2 * VOTE_COST);
+        assert_eq!(Balances::free_balance(CHARLIE), 1_000 - 3 * VOTE_COST);
+        assert_eq!(Balances::free_balance(DAVE), 1_000 - 3 * VOTE_COST);
+        assert_eq!(
+            Markets::<Test>::get(market_id).unwrap().total_staked,
+            8 * VOTE_COST
+        );
+    });
+}
+
+#[test]
+fn refunds_return_each_voters_actual_charge() {
+    new_test_ext().execute_with(|| {
+        MinWinMargin::set(1);
+        let market_id = create_market(ALICE);
+        OraclePrice::set(Some(2));
+        vote(BOB, market_id, true);
+        OraclePrice::set(Some(3));
+        vote(CHARLIE, market_id, false);
+        end_market(market_id);
+
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            market_id
+        ));
+        assert_eq!(CustomPallet::market_outcome(market_id), Some(Outcome::Void));
+        assert_eq!(Balances::free_balance(BOB), 1_000);
+        assert_eq!(Balances::free_balance(CHARLIE), 1_000);
+    });
+}
+
+#[test]
+fn vote_fails_without_any_known_price() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_market(ALICE);
+        OraclePrice::set(None);
+        assert_noop!(
+            CustomPallet::vote(RuntimeOrigin::signed(BOB), market_id, true),
+            Error::<Test>::PriceUnavailable
+        );
+    });
+}
+
+#[test]
+fn reward_cap_sends_the_excess_to_the_treasury() {
+    new_test_ext().execute_with(|| {
+        MaxRewardPerAccount::set(10);
+        let market_id = create_market(ALICE);
+        vote(BOB, market_id, true);
+        vote(CHARLIE, market_id, true);
+        vote(DAVE, market_id, false);
+        end_market(market_id);
+
+        // Each winner's even share of 27 would be 13; the cap holds it to 10.
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            market_id
+        ));
+        System::assert_has_event(
+            Event::RewardCapExcessCollected {
+                market_id,
+                amount: 6,
+            }
+            .into(),
+        );
+        assert_eq!(Balances::free_balance(BOB), 1_000 - VOTE_COST + 10);
+        assert_eq!(Balances::free_balance(CHARLIE), 1_000 - VOTE_COST + 10);
+        assert_eq!(Balances::free_balance(TREASURY), 6);
+    });
+}
+
+#[test]
+fn market_exists_and_is_resolvable_queries() {
+    new_test_ext().execute_with(|| {
+        assert!(!CustomPallet::market_exists(0));
+        assert!(!CustomPallet::is_resolvable(0));
+
+        let market_id = create_market(ALICE);
+        vote(BOB, market_id, true);
+        assert!(CustomPallet::market_exists(market_id));
+        assert!(!CustomPallet::is_resolvable(market_id));
+
+        end_market(market_id);
+        assert!(CustomPallet::is_resolvable(market_id));
+
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            market_id
+        ));
+        assert!(CustomPallet::market_exists(market_id));
+        assert!(!CustomPallet::is_resolvable(market_id));
+    });
+}
+
+#[test]
+fn voting_closes_once_the_trailing_side_cannot_catch_up() {
+    new_test_ext().execute_with(|| {
+        CloseWhenDecided::set(true);
+        MaxVotesPerMarket::set(5);
+        let market_id = create_market(ALICE);
+        vote(BOB, market_id, true);
+        vote(CHARLIE, market_id, true);
+        // Two yes against three votes still to come is not yet decided.
+        assert!(!Markets::<Test>::get(market_id).unwrap().voting_closed);
+
+        vote(DAVE, market_id, true);
+        assert!(Markets::<Test>::get(market_id).unwrap().voting_closed);
+        System::assert_last_event(Event::VotingClosed { market_id }.into());
+        assert_noop!(
+            CustomPallet::vote(RuntimeOrigin::signed(ALICE), market_id, false),
+            Error::<Test>::VotingClosed
+        );
+
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            market_id
+        ));
+        assert_eq!(CustomPallet::market_outcome(market_id), Some(Outcome::Yes));
+    });
+}
+
+fn seed_v0_market(market_id: u32, creator: u64, is_active: bool) {
+    use codec::Encode;
+
+    let market = crate::migrations::v0::Market::<Test> {
+        creator,
+        end_block: 10,
+        yes_votes: 1,
+        no_votes: 0,
+        total_staked: VOTE_COST,
+        is_active,
+        metadata: BoundedVec::truncate_from(b"will it rain?".to_vec()),
+        memes: BoundedVec::default(),
+    };
+    frame_support::storage::unhashed::put_raw(
+        &Markets::<Test>::hashed_key_for(market_id),
+        &market.encode(),
+    );
+}
+
+#[test]
+fn migration_translates_v0_markets_and_backfills_the_creator_index_once() {
+    new_test_ext().execute_with(|| {
+        // A version 0 chain: markets in the old layout and no creator index.
+        seed_v0_market(0, ALICE, false);
+        for market_id in 1..=MAX_MARKETS_PER_CREATOR + 1 {
+            seed_v0_market(market_id, ALICE, true);
+        }
+        let bob_market = MAX_MARKETS_PER_CREATOR + 2;
+        seed_v0_market(bob_market, BOB, true);
+        crate::MarketCount::<Test>::put(bob_market + 1);
+        StorageVersion::new(0).put::<CustomPallet>();
+        assert!(Markets::<Test>::try_get(0).is_err());
+
+        MigrateToV1::<Test>::on_runtime_upgrade();
+        let market = Markets::<Test>::get(0).unwrap();
+        assert_eq!(market.creator, ALICE);
+        assert_eq!((market.yes_votes, market.no_votes), (1, 0));
+        assert_eq!(market.total_staked, VOTE_COST);
+        assert!(!market.is_active && !market.voting_closed);
+        assert_eq!(market.deposit, 0);
+        assert_eq!(market.resolved_outcome, None);
+        assert_eq!(market.snapshot, None);
+        assert_eq!(&market.metadata[..], b"will it rain?");
+
+        // Only active markets are indexed, keeping the most recent ones past the cap.
+        assert_eq!(
+            MarketsByCreator::<Test>::get(ALICE).into_inner(),
+            (2..=MAX_MARKETS_PER_CREATOR + 1).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            MarketsByCreator::<Test>::get(BOB).into_inner(),
+            vec![bob_market]
+        );
+        assert_eq!(CustomPallet::on_chain_storage_version(), 1);
+
+        // Once at version 1 the migration is a no-op.
+        MarketsByCreator::<Test>::remove(BOB);
+        MigrateToV1::<Test>::on_runtime_upgrade();
+        assert!(MarketsByCreator::<Test>::get(BOB).is_empty());
+        assert_eq!(Markets::<Test>::get(bob_market).unwrap().deposit, 0);
+    });
+}
+
+#[test]
+fn loyal_winners_earn_a_bonus_and_first_timers_do_not() {
+    new_test_ext().execute_with(|| {
+        LoyaltyBonus::set(4);
+        Balances::make_free_balance_be(&TREASURY, 100);
+
+        let first = create_market(ALICE);
+        let second = create_market(ALICE);
+        vote(BOB, first, true);
+        vote(BOB, second, true);
+        vote(DAVE, second, true);
+        vote(CHARLIE, second, false);
+        end_market(first);
+
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            first
+        ));
+        assert_eq!(Balances::free_balance(TREASURY), 100);
+        assert_eq!(crate::UserStats::<Test>::get(BOB).markets_resolved, 1);
+
+        let bob_before = Balances::free_balance(BOB);
+        let dave_before = Balances::free_balance(DAVE);
+        assert_ok!(CustomPallet::release_rewards(
+            RuntimeOrigin::signed(BOB),
+            second
+        ));
+        System::assert_has_event(
+            Event::LoyaltyBonusPaid {
+                market_id: second,
+                who: BOB,
+                amount: 4,
+            }
+            .into(),
+        );
+        assert_eq!(Balances::free_balance(BOB), bob_before + 13 + 4);
+        assert_eq!(Balances::free_balance(DAVE), dave_before + 13);
+        assert_eq!(Balances::free_balance(TREASURY), 96);
+    });
+}
+
+#[test]
+fn every_error_has_a_distinct_stable_code() {
+    use scale_info::TypeInfo;
+
+    let codes = [
+        (Error::<Test>::MarketDoesNotExist, 1),
+        (Error::<Test>::MarketNotActive, 2),
+        (Error::<Test>::MarketStillActive, 3),
+        (Error::<Test>::AlreadyVoted, 4),
+        (Error::<Test>::InvalidVoteCost, 5),
+        (Error::<Test>::MetadataTooLong, 6),
+        (Error::<Test>::MemeListFull, 7),
+        (Error::<Test>::InvalidCID, 8),
+        (Error::<Test>::AttestationRequired, 9),
+        (Error::<Test>::LateStakeTooLarge, 10),
+        (Error::<Test>::MarketUnderReview, 11),
+        (Error::<Test>::TooManyMarkets, 12),
+        (Error::<Test>::NotMarketCreator, 13),
+        (Error::<Test>::MetadataLocked, 14),
+        (Error::<Test>::InsufficientDeposit, 15),
+        (Error::<Test>::VotingClosed, 16),
+        (Error::<Test>::ResolveAfterLocked, 17),
+        (Error::<Test>::ReferenceTimeNotReached, 18),
+        (Error::<Test>::EndBlockInPast, 19),
+        (Error::<Test>::InsufficientPoolBalance, 20),
+        (Error::<Test>::VoteCooldownActive, 21),
+        (Error::<Test>::CreatorCannotVote, 22),
+        (Error::<Test>::AlreadyKeeper, 23),
+        (Error::<Test>::NotKeeper, 24),
+        (Error::<Test>::PriceUnavailable, 25),
+        (Error::<Test>::KeeperBondLocked, 26),
+    ];
+    for (error, code) in &codes {
+        assert_eq!(CustomPallet::error_code(error), *code);
+    }
+
+    let distinct: std::collections::BTreeSet<u16> = codes.iter().map(|(_, code)| *code).collect();
+    assert_eq!(distinct.len(), codes.len());
+
+    // Fails when a new error is added without a code in the table above.
+    let scale_info::TypeDef::Variant(variants) = Error::<Test>::type_info().type_def else {
+        panic!("Error is an enum");
+    };
+    assert_eq!(variants.variants.len(), codes.len());
+}
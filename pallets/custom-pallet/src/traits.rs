@@ -0,0 +1,97 @@
+use core::marker::PhantomData;
+use frame_support::dispatch::DispatchResult;
+use frame_support::sp_runtime::traits::AtLeast32BitUnsigned;
+use frame_support::traits::{Currency, ReservableCurrency};
+
+/// Decides whether an account holds the attestation (e.g. KYC) required to vote.
+pub trait AttestationProvider<AccountId> {
+    fn is_attested(who: &AccountId) -> bool;
+}
+
+/// Accepts every account, i.e. voting is permissionless.
+impl<AccountId> AttestationProvider<AccountId> for () {
+    fn is_attested(_who: &AccountId) -> bool {
+        true
+    }
+}
+
+/// Computes the deposit for a new market from the creator's active market count.
+pub trait DepositScaling<Balance> {
+    fn deposit_for(base: Balance, active_markets: u32) -> Balance;
+}
+
+/// Charges the base deposit regardless of how many markets are active.
+impl<Balance> DepositScaling<Balance> for () {
+    fn deposit_for(base: Balance, _active_markets: u32) -> Balance {
+        base
+    }
+}
+
+/// The n-th concurrent market costs n times the base deposit.
+pub struct LinearDepositScaling;
+impl<Balance: AtLeast32BitUnsigned> DepositScaling<Balance> for LinearDepositScaling {
+    fn deposit_for(base: Balance, active_markets: u32) -> Balance {
+        base.saturating_mul(active_markets.saturating_add(1).into())
+    }
+}
+
+/// Adjusts the creator's reward percentage to the size of the market's pool.
+pub trait CreatorRewardCurve<Balance> {
+    fn creator_percentage(base_percentage: u32, total_pool: Balance) -> u32;
+}
+
+/// Pays the base percentage for every pool size.
+impl<Balance> CreatorRewardCurve<Balance> for () {
+    fn creator_percentage(base_percentage: u32, _total_pool: Balance) -> u32 {
+        base_percentage
+    }
+}
+
+/// Decides whether a voter on the winning side may receive a share of the pool.
+pub trait IsEligibleWinner<AccountId> {
+    fn is_eligible_winner(who: &AccountId) -> bool;
+}
+
+/// Every winning voter is paid.
+impl<AccountId> IsEligibleWinner<AccountId> for () {
+    fn is_eligible_winner(_who: &AccountId) -> bool {
+        true
+    }
+}
+
+/// Holds a creator's deposit while their market runs, e.g. staking it for yield.
+pub trait DepositStaker<AccountId, C: Currency<AccountId>> {
+    /// Takes `amount` of `who`'s free balance into the staker's custody.
+    fn stake(who: &AccountId, amount: C::Balance) -> DispatchResult;
+
+    /// Releases the `amount` staked for `who`, handing the principal and any accrued yield back
+    /// to the pallet to settle.
+    fn unstake(who: &AccountId, amount: C::Balance) -> C::NegativeImbalance;
+}
+
+/// Keeps the deposit reserved in `C` and earns nothing.
+pub struct ReserveDeposit<C>(PhantomData<C>);
+impl<AccountId, C: ReservableCurrency<AccountId>> DepositStaker<AccountId, C>
+    for ReserveDeposit<C>
+{
+    fn stake(who: &AccountId, amount: C::Balance) -> DispatchResult {
+        C::reserve(who, amount)
+    }
+
+    fn unstake(who: &AccountId, amount: C::Balance) -> C::NegativeImbalance {
+        C::slash_reserved(who, amount).0
+    }
+}
+
+/// Converts amounts in the unit `VoteCost` is denominated in, e.g. USD, to the native token.
+pub trait PriceOracle<Balance> {
+    /// `None` when no current price is available.
+    fn to_native(amount: Balance) -> Option<Balance>;
+}
+
+/// `VoteCost` is already denominated in the native token.
+impl<Balance> PriceOracle<Balance> for () {
+    fn to_native(amount: Balance) -> Option<Balance> {
+        Some(amount)
+    }
+}
@@ -36,15 +36,15 @@ use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	traits::Block as BlockT,
 	transaction_validity::{TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult,
+	ApplyExtrinsicResult, Permill,
 };
 use sp_version::RuntimeVersion;
 
 // Local module imports
 use super::{
-	AccountId, Balance, Block, ConsensusHook, Executive, InherentDataExt, Nonce, ParachainSystem,
-	Runtime, RuntimeCall, RuntimeGenesisConfig, SessionKeys, System, TransactionPayment,
-	SLOT_DURATION, VERSION,
+	AccountId, Balance, Block, BlockNumber, ConsensusHook, CustomPallet, Executive, Hash,
+	InherentDataExt, Nonce, ParachainSystem, Runtime, RuntimeCall, RuntimeGenesisConfig,
+	SessionKeys, System, TransactionPayment, SLOT_DURATION, VERSION,
 };
 
 // we move some impls outside so we can easily use them with `docify`.
@@ -216,6 +216,65 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl custom_pallet::runtime_api::PredictionMarketApi<Block, AccountId, Balance, BlockNumber, Hash>
+		for Runtime
+	{
+		fn creator_projected_reward(market_id: u32) -> Balance {
+			CustomPallet::creator_projected_reward(market_id)
+		}
+
+		fn market_participation(market_id: u32) -> Option<(u32, u32, Balance)> {
+			CustomPallet::market_participation(market_id)
+		}
+
+		fn market_outcome(market_id: u32) -> Option<custom_pallet::Outcome> {
+			CustomPallet::market_outcome(market_id)
+		}
+
+		fn export_outcome(
+			market_id: u32,
+		) -> Option<custom_pallet::OutcomeAttestation<Hash, BlockNumber, Balance>> {
+			CustomPallet::export_outcome(market_id)
+		}
+
+		fn verify_outcome_attestation(
+			attestation: custom_pallet::OutcomeAttestation<Hash, BlockNumber, Balance>,
+		) -> bool {
+			CustomPallet::verify_outcome_attestation(&attestation)
+		}
+
+		fn validate_create_market(
+			creator: AccountId,
+			metadata: Vec<u8>,
+		) -> Result<(), sp_runtime::DispatchError> {
+			CustomPallet::validate_create_market(&creator, metadata)
+		}
+
+		fn effective_vote_power(market_id: u32, who: AccountId) -> u128 {
+			CustomPallet::effective_vote_power(market_id, &who)
+		}
+
+		fn simulate_vote_impact(
+			market_id: u32,
+			vote_yes: bool,
+			stake: Balance,
+		) -> Option<(Permill, Permill)> {
+			CustomPallet::simulate_vote_impact(market_id, vote_yes, stake)
+		}
+
+		fn account_pnl(who: AccountId) -> (Balance, Balance) {
+			CustomPallet::account_pnl(&who)
+		}
+
+		fn market_exists(market_id: u32) -> bool {
+			CustomPallet::market_exists(market_id)
+		}
+
+		fn is_resolvable(market_id: u32) -> bool {
+			CustomPallet::is_resolvable(market_id)
+		}
+	}
+
 	#[cfg(feature = "try-runtime")]
 	impl frame_try_runtime::TryRuntime<Block> for Runtime {
 		fn on_runtime_upgrade(checks: frame_try_runtime::UpgradeCheckSelect) -> (Weight, Weight) {
@@ -50,7 +50,7 @@ use polkadot_runtime_common::{
 	xcm_sender::NoPriceForMessageDelivery, BlockHashCount, SlowAdjustingFeeUpdate,
 };
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
-use sp_runtime::Perbill;
+use sp_runtime::{traits::AccountIdConversion, Perbill, Permill};
 use sp_version::RuntimeVersion;
 use xcm::latest::prelude::BodyId;
 
@@ -60,8 +60,8 @@ use super::{
 	AccountId, Aura, Balance, Balances, Block, BlockNumber, CollatorSelection, ConsensusHook, Hash,
 	MessageQueue, Nonce, PalletInfo, ParachainSystem, Runtime, RuntimeCall, RuntimeEvent,
 	RuntimeFreezeReason, RuntimeHoldReason, RuntimeOrigin, RuntimeTask, Session, SessionKeys,
-	System, WeightToFee, XcmpQueue, AVERAGE_ON_INITIALIZE_RATIO, EXISTENTIAL_DEPOSIT, HOURS,
-	MAXIMUM_BLOCK_WEIGHT, MICRO_UNIT, NORMAL_DISPATCH_RATIO, SLOT_DURATION, VERSION,
+	System, Timestamp, WeightToFee, XcmpQueue, AVERAGE_ON_INITIALIZE_RATIO, EXISTENTIAL_DEPOSIT,
+	HOURS, MAXIMUM_BLOCK_WEIGHT, MICRO_UNIT, NORMAL_DISPATCH_RATIO, SLOT_DURATION, VERSION,
 };
 use xcm_config::{RelayLocation, XcmOriginToTransactDispatchOrigin};
 
@@ -326,6 +326,33 @@ parameter_types! {
     pub const VoteCost: Balance = 0; // Or whatever amount you want to set
     pub const CreatorRewardPercentage: u32 = 5; // 5%
     pub const MarketDuration: u32 = 100; // Duration in blocks
+    pub const StakeFreezeWindow: u32 = 0; // Disabled: no late-stake freeze
+    pub const LateStakeCap: Balance = Balance::MAX;
+    pub const MinWinMargin: u32 = 0; // Any lead wins
+    pub const CreationDeposit: Balance = 0; // Base deposit, scaled per active market
+    pub const MaxMarketsPerCreator: u32 = 50;
+    pub const MinWinnersToPay: u32 = 1;
+    pub const DepositOnVoid: custom_pallet::DepositPolicy = custom_pallet::DepositPolicy::Return;
+    pub TreasuryAccount: AccountId = PalletId(*b"py/trsry").into_account_truncating();
+    pub const MaxVotesPerMarket: u32 = 1_000;
+    pub const FreeVoteQuota: u32 = 0; // No fee-free votes
+    pub const BurnPercentage: Permill = Permill::zero(); // No pool burn
+    pub const AllowRetroactiveEndBlock: bool = false;
+    pub const HousePercentage: Permill = Permill::zero(); // No house edge
+    pub const EscalationDelay: u32 = 14_400; // ~1 day of 6s blocks
+    pub const MaxEscalationChecksPerBlock: u32 = 10;
+    pub const MaxEscalatedMarkets: u32 = 100;
+    pub const SnapshotOffset: u32 = 0; // Resolve on final tallies
+    pub const EmitBlockSummary: bool = false;
+    pub const VoteCooldown: u32 = 0; // No cross-market throttle
+    pub const AllowCreatorVoting: bool = true;
+    pub const KeeperBond: Balance = 0;
+    pub const KeeperTip: Balance = 0; // Resolution is unpaid
+    pub const KeeperUnbondingPeriod: u32 = 100_800; // ~7 days of 6s blocks
+    pub const MaxRewardPerAccount: Balance = Balance::MAX; // Uncapped
+    pub const CloseWhenDecided: bool = false;
+    pub const LoyaltyThreshold: u32 = 5;
+    pub const LoyaltyBonus: Balance = 0; // No loyalty bonus
 }
 
 // Then implement the config for your pallet
@@ -336,4 +363,39 @@ impl custom_pallet::Config for Runtime {
     type VoteCost = VoteCost;
     type CreatorRewardPercentage = CreatorRewardPercentage;
     type MarketDuration = MarketDuration;
+    type VoterAttestation = ();
+    type StakeFreezeWindow = StakeFreezeWindow;
+    type LateStakeCap = LateStakeCap;
+    type GovernanceOrigin = EnsureRoot<AccountId>;
+    type MinWinMargin = MinWinMargin;
+    type CreationDeposit = CreationDeposit;
+    type DepositScaling = custom_pallet::traits::LinearDepositScaling;
+    type DepositStaker = custom_pallet::traits::ReserveDeposit<Balances>;
+    type MaxMarketsPerCreator = MaxMarketsPerCreator;
+    type MinWinnersToPay = MinWinnersToPay;
+    type CreatorRewardCurve = ();
+    type DepositOnVoid = DepositOnVoid;
+    type TreasuryAccount = TreasuryAccount;
+    type MaxVotesPerMarket = MaxVotesPerMarket;
+    type FreeVoteQuota = FreeVoteQuota;
+    type ReferenceTime = Timestamp;
+    type BurnPercentage = BurnPercentage;
+    type AllowRetroactiveEndBlock = AllowRetroactiveEndBlock;
+    type HousePercentage = HousePercentage;
+    type EscalationDelay = EscalationDelay;
+    type MaxEscalationChecksPerBlock = MaxEscalationChecksPerBlock;
+    type MaxEscalatedMarkets = MaxEscalatedMarkets;
+    type WinnerEligibility = ();
+    type SnapshotOffset = SnapshotOffset;
+    type EmitBlockSummary = EmitBlockSummary;
+    type VoteCooldown = VoteCooldown;
+    type AllowCreatorVoting = AllowCreatorVoting;
+    type KeeperBond = KeeperBond;
+    type KeeperTip = KeeperTip;
+    type KeeperUnbondingPeriod = KeeperUnbondingPeriod;
+    type PriceOracle = ();
+    type MaxRewardPerAccount = MaxRewardPerAccount;
+    type CloseWhenDecided = CloseWhenDecided;
+    type LoyaltyThreshold = LoyaltyThreshold;
+    type LoyaltyBonus = LoyaltyBonus;
 }
\ No newline at end of file